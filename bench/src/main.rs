@@ -1,5 +1,4 @@
 use country_boundaries::{self, CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
-use std::fs;
 use std::time::Instant;
 
 fn main() {
@@ -29,11 +28,31 @@ fn main() {
         checks,
         time_spent_on_boundaries.as_secs_f64(),
         time_spent_on_boundaries.as_nanos() / checks
+    );
+
+    let batch_size = 10_000_000;
+    let positions: Vec<LatLon> = (0..batch_size)
+        .map(|_| latlon(rng.f64() * 180.0 - 90.0, rng.f64() * 360.0 - 180.0))
+        .collect();
+
+    let time = Instant::now();
+    let _: Vec<Vec<&str>> = positions.iter().map(|p| boundaries.ids(*p)).collect();
+    let time_spent_single = time.elapsed();
+
+    let time = Instant::now();
+    let _ = boundaries.ids_batch(&positions);
+    let time_spent_batch = time.elapsed();
+
+    println!(
+        "Querying {} random locations one by one took {:.2} seconds, in a batch {:.2} seconds",
+        batch_size,
+        time_spent_single.as_secs_f64(),
+        time_spent_batch.as_secs_f64()
     )
 }
 
 fn boundaries() -> CountryBoundaries {
-    return CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180).unwrap();
+    CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180).unwrap()
 }
 
 fn latlon(latitude: f64, longitude: f64) -> LatLon {
@@ -0,0 +1,177 @@
+use country_boundaries::{BoundingBox, CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: country-boundaries [--data <file.ser>] <command> [<args>]
+
+Commands:
+  lookup <lat> <lon>                          ids of the regions at the given position
+  bbox <minlat> <minlon> <maxlat> <maxlon>    ids of the regions containing / intersecting
+                                              the given bounding box
+  info [<file.ser>]                           raster size, number of ids and cell statistics
+  batch                                       ids for each 'lat,lon[,...]' line of CSV read
+                                              from stdin, written as 'lat,lon[,...],ids' to
+                                              stdout. Empty lines and lines starting with '#'
+                                              are skipped
+
+Options:
+  --data <file.ser>    boundaries file to use instead of the default data
+                       (ODbL licensed, (c) OpenStreetMap contributors)
+  -h, --help           print this help
+";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(CliError::Failed(e)) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+enum CliError {
+    Usage(String),
+    Failed(Box<dyn Error>),
+}
+
+impl<E: Into<Box<dyn Error>>> From<E> for CliError {
+    fn from(error: E) -> Self {
+        Self::Failed(error.into())
+    }
+}
+
+fn run(args: &[String]) -> Result<(), CliError> {
+    let mut data: Option<&str> = None;
+    let mut positional: Vec<&str> = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return Ok(());
+            }
+            "--data" => match args.next() {
+                Some(file) => data = Some(file),
+                None => return Err(usage("--data requires a file")),
+            },
+            _ => positional.push(arg),
+        }
+    }
+
+    let out = io::stdout();
+    let mut out = BufWriter::new(out.lock());
+    match positional.as_slice() {
+        ["lookup", lat, lon] => {
+            let boundaries = load(data)?;
+            let position = LatLon::new(number(lat)?, number(lon)?)?;
+            writeln!(out, "{}", boundaries.ids(position).join(","))?;
+        }
+        ["bbox", min_lat, min_lon, max_lat, max_lon] => {
+            let boundaries = load(data)?;
+            let bounds = BoundingBox::new(
+                number(min_lat)?,
+                number(min_lon)?,
+                number(max_lat)?,
+                number(max_lon)?,
+            )?;
+            let mut containing: Vec<&str> = boundaries.containing_ids(bounds).into_iter().collect();
+            let mut intersecting: Vec<&str> =
+                boundaries.intersecting_ids(bounds).into_iter().collect();
+            containing.sort_unstable();
+            intersecting.sort_unstable();
+            writeln!(out, "containing: {}", containing.join(","))?;
+            writeln!(out, "intersecting: {}", intersecting.join(","))?;
+        }
+        ["info"] => info(&mut out, &load(data)?)?,
+        ["info", file] => info(&mut out, &load(Some(file))?)?,
+        ["batch"] => batch(&load(data)?, io::stdin().lock(), &mut out)?,
+        [] => return Err(usage("no command given")),
+        [command, ..] => {
+            return Err(usage(&format!(
+                "unknown command or wrong number of arguments for '{command}'"
+            )))
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn info(out: &mut impl Write, boundaries: &CountryBoundaries) -> io::Result<()> {
+    let statistics = boundaries.statistics();
+    let cells = statistics.raster_width * statistics.raster_height;
+    writeln!(
+        out,
+        "raster size: {}x{}",
+        statistics.raster_width, statistics.raster_height
+    )?;
+    writeln!(out, "ids: {}", statistics.ids)?;
+    writeln!(out, "cells: {cells}")?;
+    writeln!(out, "empty cells: {}", statistics.empty_cells)?;
+    writeln!(
+        out,
+        "cells with geometry: {}",
+        statistics.cells_with_geometry
+    )?;
+    writeln!(out, "max ids per cell: {}", statistics.max_ids_per_cell)?;
+    writeln!(out, "points: {}", statistics.points)?;
+    Ok(())
+}
+
+fn batch(
+    boundaries: &CountryBoundaries,
+    input: impl BufRead,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    let mut is_first_line = true;
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // only the first two columns are read, any further columns are passed through
+        let mut fields = line
+            .split(',')
+            .map(|field| field.trim().parse::<f64>().ok());
+        let lat = fields.next().flatten();
+        let lon = fields.next().flatten();
+        match (lat, lon) {
+            (Some(lat), Some(lon)) => {
+                let position =
+                    LatLon::new(lat, lon).map_err(|e| format!("line {}: {e}", index + 1))?;
+                writeln!(out, "{line},{}", boundaries.ids(position).join(";"))?;
+            }
+            (None, None) if is_first_line => writeln!(out, "{line},ids")?,
+            _ => return Err(format!("line {}: expected 'lat,lon'", index + 1).into()),
+        }
+        is_first_line = false;
+    }
+    Ok(())
+}
+
+fn load(file: Option<&str>) -> Result<CountryBoundaries, CliError> {
+    let boundaries = match file {
+        Some(file) => CountryBoundaries::from_reader(BufReader::new(File::open(file)?))?,
+        None => CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?,
+    };
+    Ok(boundaries)
+}
+
+fn number(value: &str) -> Result<f64, CliError> {
+    value
+        .parse()
+        .map_err(|_| usage(&format!("'{value}' is not a number")))
+}
+
+fn usage(message: &str) -> CliError {
+    CliError::Usage(String::from(message))
+}
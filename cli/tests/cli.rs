@@ -0,0 +1,107 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+#[test]
+fn lookup() {
+    let output = run(&["lookup", "33.0", "-97.0"], "");
+    assert!(output.status.success());
+    assert_eq!("US-TX,US\n", stdout(&output));
+}
+
+#[test]
+fn lookup_at_sea() {
+    let output = run(&["lookup", "-40.0", "-120.0"], "");
+    assert!(output.status.success());
+    assert_eq!("\n", stdout(&output));
+}
+
+#[test]
+fn lookup_invalid_position() {
+    assert!(!run(&["lookup", "91.0", "0.0"], "").status.success());
+    assert!(!run(&["lookup", "a", "0.0"], "").status.success());
+    assert!(!run(&["lookup", "1.0"], "").status.success());
+}
+
+#[test]
+fn bbox() {
+    let output = run(&["bbox", "66.0", "178.0", "68.0", "-178.0"], "");
+    assert!(output.status.success());
+    assert_eq!("containing: RU\nintersecting: RU\n", stdout(&output));
+}
+
+#[test]
+fn info() {
+    let output = run(&["info"], "");
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with("raster size: 360x180\n"));
+    assert!(stdout.contains("\ncells: 64800\n"));
+}
+
+#[test]
+fn info_of_missing_file() {
+    assert!(!run(&["info", "does-not-exist.ser"], "").status.success());
+}
+
+#[test]
+fn batch() {
+    let output = run(&["batch"], "lat,lon\n33.0,-97.0\n\n47.6973, 8.6910\n");
+    assert!(output.status.success());
+    assert_eq!(
+        "lat,lon,ids\n33.0,-97.0,US-TX;US\n47.6973, 8.6910,DE\n",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn batch_with_header_after_empty_and_comment_lines() {
+    let output = run(&["batch"], "\n# positions\nlat,lon\n33.0,-97.0\n");
+    assert!(output.status.success());
+    assert_eq!("lat,lon,ids\n33.0,-97.0,US-TX;US\n", stdout(&output));
+}
+
+#[test]
+fn batch_with_more_columns_and_no_header() {
+    let output = run(&["batch"], "33.0,-97.0,Dallas\n47.6973,8.6910,Büsingen,x\n");
+    assert!(output.status.success());
+    assert_eq!(
+        "33.0,-97.0,Dallas,US-TX;US\n47.6973,8.6910,Büsingen,x,DE\n",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn batch_with_invalid_line() {
+    let output = run(&["batch"], "33.0,-97.0\nfoo\n");
+    assert!(!output.status.success());
+    // only a first line in which neither latitude nor longitude is a number is a header
+    let output = run(&["batch"], "33.0,lon\n33.0,-97.0\n");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn unknown_command() {
+    assert_eq!(Some(2), run(&["foo"], "").status.code());
+    assert_eq!(Some(2), run(&[], "").status.code());
+}
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_country-boundaries"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
@@ -0,0 +1,78 @@
+#ifndef COUNTRY_BOUNDARIES_H
+#define COUNTRY_BOUNDARIES_H
+
+/* Generated with cbindgen from ffi/src/lib.rs, do not edit manually */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Boundaries loaded with `cb_load_from_bytes`, to be freed with `cb_free`
+ */
+typedef struct CbCountryBoundaries CbCountryBoundaries;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Loads the boundaries from the `len` bytes of a boundaries file (`*.ser`) at `bytes`.
+ *
+ * Returns NULL if the data is invalid. The result must be freed with `cb_free`.
+ *
+ * # Safety
+ * `bytes` must point to at least `len` readable bytes.
+ */
+struct CbCountryBoundaries *cb_load_from_bytes(const uint8_t *bytes, size_t len);
+
+/**
+ * Writes the ids of the regions the given position is in, ordered by size of the region
+ * ascending and separated by commas, as a null-terminated string into `buffer`, e.g.
+ * `US-TX,US`.
+ *
+ * Like `snprintf`, at most `buffer_size` bytes including the terminating null are written and
+ * the length of the whole string without the terminating null is returned, so if the result
+ * is not smaller than `buffer_size`, the ids have been truncated. `buffer` may be NULL if
+ * `buffer_size` is 0.
+ *
+ * Returns -1 if `boundaries` is NULL or the position is invalid.
+ *
+ * # Safety
+ * `boundaries` must have been returned by `cb_load_from_bytes` and not been freed yet and
+ * `buffer` must point to at least `buffer_size` writable bytes.
+ */
+ptrdiff_t cb_ids(const struct CbCountryBoundaries *boundaries,
+                 double latitude,
+                 double longitude,
+                 char *buffer,
+                 size_t buffer_size);
+
+/**
+ * Returns 1 if the given position is in the region with the null-terminated `id`, 0 if not
+ * and -1 if any parameter is NULL or the position is invalid.
+ *
+ * # Safety
+ * `boundaries` must have been returned by `cb_load_from_bytes` and not been freed yet and
+ * `id` must be a null-terminated string.
+ */
+int cb_is_in(const struct CbCountryBoundaries *boundaries,
+             double latitude,
+             double longitude,
+             const char *id);
+
+/**
+ * Frees the boundaries returned by `cb_load_from_bytes`. Does nothing if `boundaries` is NULL.
+ *
+ * # Safety
+ * `boundaries` must have been returned by `cb_load_from_bytes` and not been freed yet.
+ */
+void cb_free(struct CbCountryBoundaries *boundaries);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* COUNTRY_BOUNDARIES_H */
@@ -0,0 +1,171 @@
+//! C API for country-boundaries.
+//!
+//! The header `include/country_boundaries.h` is generated from this file with cbindgen, see
+//! `cbindgen.toml`. `tests/test.c` shows how to use it.
+
+use country_boundaries::LatLon;
+use std::ffi::{c_char, c_int, CStr};
+use std::ptr;
+
+/// Boundaries loaded with `cb_load_from_bytes`, to be freed with `cb_free`
+pub struct CountryBoundaries(country_boundaries::CountryBoundaries);
+
+/// Loads the boundaries from the `len` bytes of a boundaries file (`*.ser`) at `bytes`.
+///
+/// Returns NULL if the data is invalid. The result must be freed with `cb_free`.
+///
+/// # Safety
+/// `bytes` must point to at least `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cb_load_from_bytes(
+    bytes: *const u8,
+    len: usize,
+) -> *mut CountryBoundaries {
+    if bytes.is_null() {
+        return ptr::null_mut();
+    }
+    let bytes = std::slice::from_raw_parts(bytes, len);
+    match country_boundaries::CountryBoundaries::from_reader(bytes) {
+        Ok(boundaries) => Box::into_raw(Box::new(CountryBoundaries(boundaries))),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Writes the ids of the regions the given position is in, ordered by size of the region
+/// ascending and separated by commas, as a null-terminated string into `buffer`, e.g.
+/// `US-TX,US`.
+///
+/// Like `snprintf`, at most `buffer_size` bytes including the terminating null are written and
+/// the length of the whole string without the terminating null is returned, so if the result
+/// is not smaller than `buffer_size`, the ids have been truncated. `buffer` may be NULL if
+/// `buffer_size` is 0.
+///
+/// Returns -1 if `boundaries` is NULL or the position is invalid.
+///
+/// # Safety
+/// `boundaries` must have been returned by `cb_load_from_bytes` and not been freed yet and
+/// `buffer` must point to at least `buffer_size` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cb_ids(
+    boundaries: *const CountryBoundaries,
+    latitude: f64,
+    longitude: f64,
+    buffer: *mut c_char,
+    buffer_size: usize,
+) -> isize {
+    let Some(boundaries) = boundaries.as_ref() else {
+        return -1;
+    };
+    let Ok(position) = LatLon::new(latitude, longitude) else {
+        return -1;
+    };
+    let ids = boundaries.0.ids(position).join(",");
+    if !buffer.is_null() && buffer_size > 0 {
+        let len = ids.len().min(buffer_size - 1);
+        ptr::copy_nonoverlapping(ids.as_ptr().cast(), buffer, len);
+        *buffer.add(len) = 0;
+    }
+    ids.len() as isize
+}
+
+/// Returns 1 if the given position is in the region with the null-terminated `id`, 0 if not
+/// and -1 if any parameter is NULL or the position is invalid.
+///
+/// # Safety
+/// `boundaries` must have been returned by `cb_load_from_bytes` and not been freed yet and
+/// `id` must be a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cb_is_in(
+    boundaries: *const CountryBoundaries,
+    latitude: f64,
+    longitude: f64,
+    id: *const c_char,
+) -> c_int {
+    let Some(boundaries) = boundaries.as_ref() else {
+        return -1;
+    };
+    if id.is_null() {
+        return -1;
+    }
+    let Ok(position) = LatLon::new(latitude, longitude) else {
+        return -1;
+    };
+    // ids are ASCII, so an id that is not valid UTF-8 is not in the data
+    let Ok(id) = CStr::from_ptr(id).to_str() else {
+        return 0;
+    };
+    boundaries.0.is_in(position, id).into()
+}
+
+/// Frees the boundaries returned by `cb_load_from_bytes`. Does nothing if `boundaries` is NULL.
+///
+/// # Safety
+/// `boundaries` must have been returned by `cb_load_from_bytes` and not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn cb_free(boundaries: *mut CountryBoundaries) {
+    if !boundaries.is_null() {
+        drop(Box::from_raw(boundaries));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use country_boundaries::BOUNDARIES_ODBL_360X180;
+
+    fn load() -> *mut CountryBoundaries {
+        let bytes = BOUNDARIES_ODBL_360X180;
+        let boundaries = unsafe { cb_load_from_bytes(bytes.as_ptr(), bytes.len()) };
+        assert!(!boundaries.is_null());
+        boundaries
+    }
+
+    fn ids(boundaries: *const CountryBoundaries, buffer_size: usize) -> (isize, String) {
+        let mut buffer = vec![1 as c_char; buffer_size];
+        let len = unsafe { cb_ids(boundaries, 33.0, -97.0, buffer.as_mut_ptr(), buffer_size) };
+        let ids = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+        (len, ids.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn load_invalid_data() {
+        let bytes = [1, 2, 3];
+        assert!(unsafe { cb_load_from_bytes(bytes.as_ptr(), bytes.len()) }.is_null());
+        assert!(unsafe { cb_load_from_bytes(ptr::null(), 0) }.is_null());
+    }
+
+    #[test]
+    fn ids_are_written_to_buffer() {
+        let boundaries = load();
+        assert_eq!((8, "US-TX,US".to_string()), ids(boundaries, 100));
+        assert_eq!((8, "US-TX".to_string()), ids(boundaries, 6));
+        assert_eq!((8, "".to_string()), ids(boundaries, 1));
+        assert_eq!(8, unsafe {
+            cb_ids(boundaries, 33.0, -97.0, ptr::null_mut(), 0)
+        });
+        assert_eq!(-1, unsafe {
+            cb_ids(boundaries, 91.0, 0.0, ptr::null_mut(), 0)
+        });
+        assert_eq!(-1, unsafe {
+            cb_ids(ptr::null(), 0.0, 0.0, ptr::null_mut(), 0)
+        });
+        unsafe { cb_free(boundaries) };
+    }
+
+    #[test]
+    fn is_in() {
+        let boundaries = load();
+        assert_eq!(1, unsafe {
+            cb_is_in(boundaries, 33.0, -97.0, c"US-TX".as_ptr())
+        });
+        assert_eq!(0, unsafe {
+            cb_is_in(boundaries, 33.0, -97.0, c"DE".as_ptr())
+        });
+        assert_eq!(-1, unsafe {
+            cb_is_in(boundaries, 91.0, 0.0, c"DE".as_ptr())
+        });
+        assert_eq!(-1, unsafe { cb_is_in(boundaries, 0.0, 0.0, ptr::null()) });
+        unsafe { cb_free(boundaries) };
+        unsafe { cb_free(ptr::null_mut()) };
+    }
+}
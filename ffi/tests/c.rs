@@ -0,0 +1,45 @@
+//! Compiles `test.c` with the C compiler in `CC` (or `cc`), links it against the cdylib and runs
+//! it.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The directory the cdylib is built into for the tests, i.e. `target/debug/deps`, next to the
+/// test executable
+fn library_dir() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    exe.parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn c_test_program() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let library_dir = library_dir();
+    let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_ffi");
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let status = Command::new(compiler)
+        .arg(manifest_dir.join("tests/test.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&library_dir)
+        .arg("-lcountry_boundaries_ffi")
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("unable to run the C compiler");
+    assert!(status.success(), "compiling test.c failed");
+
+    let output = Command::new(&program)
+        .arg(manifest_dir.join("../data/boundaries360x180.ser"))
+        .env("LD_LIBRARY_PATH", &library_dir)
+        .env("DYLD_LIBRARY_PATH", &library_dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,90 @@
+/*
+ * Checks the C API by querying the boundaries file given as the first argument.
+ *
+ *   cargo build -p country-boundaries-ffi
+ *   cc ffi/tests/test.c -I ffi/include -L target/debug -lcountry_boundaries_ffi -o target/test_ffi
+ *   LD_LIBRARY_PATH=target/debug target/test_ffi data/boundaries360x180.ser
+ */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "country_boundaries.h"
+
+static int failures = 0;
+
+static void check(int condition, const char *description) {
+    if (!condition) {
+        fprintf(stderr, "FAILED: %s\n", description);
+        failures++;
+    }
+}
+
+static uint8_t *read_file(const char *path, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    if (!file) return NULL;
+    fseek(file, 0, SEEK_END);
+    *len = (size_t) ftell(file);
+    fseek(file, 0, SEEK_SET);
+    uint8_t *bytes = malloc(*len);
+    if (bytes && fread(bytes, 1, *len, file) != *len) {
+        free(bytes);
+        bytes = NULL;
+    }
+    fclose(file);
+    return bytes;
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s <boundaries.ser>\n", argv[0]);
+        return 2;
+    }
+    size_t len;
+    uint8_t *bytes = read_file(argv[1], &len);
+    if (!bytes) {
+        fprintf(stderr, "unable to read %s\n", argv[1]);
+        return 2;
+    }
+
+    check(cb_load_from_bytes(bytes, 3) == NULL, "loading invalid data returns NULL");
+
+    const uint8_t zero_width[] = {
+        0x00, 0x02,             /* version number */
+        0x00, 0x00, 0x00, 0x00, /* geometry sizes map length */
+        0x00, 0x00, 0x00, 0x00, /* raster width */
+        0x00, 0x00, 0x00, 0x01, /* raster size */
+        0x00, 0x00,             /* empty cell */
+    };
+    check(cb_load_from_bytes(zero_width, sizeof(zero_width)) == NULL,
+          "loading a raster of zero width returns NULL");
+
+    CbCountryBoundaries *boundaries = cb_load_from_bytes(bytes, len);
+    free(bytes);
+    check(boundaries != NULL, "loading data");
+    if (!boundaries) return 1;
+
+    char buffer[64];
+    ptrdiff_t written = cb_ids(boundaries, 33.0, -97.0, buffer, sizeof(buffer));
+    check(written == 8 && strcmp(buffer, "US-TX,US") == 0, "ids in Texas");
+
+    written = cb_ids(boundaries, 33.0, -97.0, buffer, 4);
+    check(written == 8 && strcmp(buffer, "US-") == 0, "ids truncated to buffer");
+
+    written = cb_ids(boundaries, 33.0, -97.0, NULL, 0);
+    check(written == 8, "length of ids without buffer");
+
+    written = cb_ids(boundaries, 91.0, 0.0, buffer, sizeof(buffer));
+    check(written == -1, "ids at invalid position");
+
+    check(cb_is_in(boundaries, 33.0, -97.0, "US-TX") == 1, "is in Texas");
+    check(cb_is_in(boundaries, 33.0, -97.0, "DE") == 0, "is not in Germany");
+    check(cb_is_in(boundaries, 91.0, 0.0, "DE") == -1, "is in at invalid position");
+
+    cb_free(boundaries);
+    cb_free(NULL);
+
+    if (failures > 0) return 1;
+    printf("all checks passed\n");
+    return 0;
+}
@@ -0,0 +1,177 @@
+//! Python bindings for country-boundaries, with lookups for whole NumPy arrays of positions.
+//!
+//! Build and install into the current virtualenv with `maturin develop --release` in this
+//! directory and run the tests with `pytest tests`.
+//!
+//! ```python
+//! import numpy as np
+//! import country_boundaries
+//!
+//! country_boundaries.ids(np.array([33.0, 52.5]), np.array([-97.0, 13.4]))
+//! # array(['US-TX', 'DE'], dtype=object)
+//!
+//! boundaries = country_boundaries.CountryBoundaries(country_boundaries.BOUNDARIES_ODBL_60X30)
+//! boundaries.ids(33.0, -97.0)
+//! # ['US-TX', 'US']
+//! ```
+
+use country_boundaries::{
+    CountryBoundaries, LatLon, BOUNDARIES_ODBL_180X90, BOUNDARIES_ODBL_360X180,
+    BOUNDARIES_ODBL_60X30,
+};
+use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Boundaries loaded from the bytes of a boundaries file (`*.ser`)
+#[pyclass(frozen, name = "CountryBoundaries", module = "country_boundaries")]
+struct PyCountryBoundaries(CountryBoundaries);
+
+#[pymethods]
+impl PyCountryBoundaries {
+    #[new]
+    fn new(data: &[u8]) -> PyResult<Self> {
+        CountryBoundaries::from_reader(data)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Returns the ids of the regions the given position is in, ordered by size of the region
+    /// ascending
+    fn ids(&self, latitude: f64, longitude: f64) -> PyResult<Vec<String>> {
+        let position = to_latlon(latitude, longitude)?;
+        Ok(self.0.ids(position).into_iter().map(String::from).collect())
+    }
+
+    /// Returns whether the given position is in the region with the given id
+    fn is_in(&self, latitude: f64, longitude: f64, id: &str) -> PyResult<bool> {
+        Ok(self.0.is_in(to_latlon(latitude, longitude)?, id))
+    }
+
+    /// Returns the ids of all regions in the data
+    fn all_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.0.ids_iter().map(String::from).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Returns an array with the primary id, i.e. the id of the smallest region, for each of the
+    /// positions given by the arrays of `latitudes` and `longitudes`, or `None` where the
+    /// position is in no region or is invalid (e.g. NaN)
+    fn primary_ids<'py>(
+        &self,
+        py: Python<'py>,
+        latitudes: PyReadonlyArray1<'py, f64>,
+        longitudes: PyReadonlyArray1<'py, f64>,
+    ) -> PyResult<Bound<'py, PyArray1<Py<PyAny>>>> {
+        primary_ids_array(py, &self.0, latitudes, longitudes)
+    }
+}
+
+/// Returns an array with the primary id for each of the positions given by the arrays of
+/// `latitudes` and `longitudes`, looked up in the data with a raster of 360x180 cells, see
+/// `CountryBoundaries.primary_ids`
+#[pyfunction]
+fn ids<'py>(
+    py: Python<'py>,
+    latitudes: PyReadonlyArray1<'py, f64>,
+    longitudes: PyReadonlyArray1<'py, f64>,
+) -> PyResult<Bound<'py, PyArray1<Py<PyAny>>>> {
+    static DEFAULT: OnceLock<CountryBoundaries> = OnceLock::new();
+    let boundaries = DEFAULT.get_or_init(|| {
+        CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180).expect("embedded data is valid")
+    });
+    primary_ids_array(py, boundaries, latitudes, longitudes)
+}
+
+fn primary_ids_array<'py>(
+    py: Python<'py>,
+    boundaries: &CountryBoundaries,
+    latitudes: PyReadonlyArray1<'py, f64>,
+    longitudes: PyReadonlyArray1<'py, f64>,
+) -> PyResult<Bound<'py, PyArray1<Py<PyAny>>>> {
+    let latitudes = latitudes.as_array();
+    let longitudes = longitudes.as_array();
+    if latitudes.len() != longitudes.len() {
+        return Err(PyValueError::new_err(format!(
+            "latitudes and longitudes must have the same length, but have {} and {}",
+            latitudes.len(),
+            longitudes.len()
+        )));
+    }
+    let ids = py.detach(|| primary_ids(boundaries, latitudes.iter(), longitudes.iter()));
+
+    // there are only a few hundred different ids, so each is only converted once
+    let mut strings: HashMap<&str, Py<PyAny>> = HashMap::new();
+    let objects: Vec<Py<PyAny>> = ids
+        .into_iter()
+        .map(|id| match id {
+            Some(id) => strings
+                .entry(id)
+                .or_insert_with(|| PyString::new(py, id).into_any().unbind())
+                .clone_ref(py),
+            None => py.None(),
+        })
+        .collect();
+    Ok(PyArray1::from_vec(py, objects))
+}
+
+/// Returns the id of the smallest region each of the given positions is in, if any
+fn primary_ids<'a, 'b>(
+    boundaries: &'a CountryBoundaries,
+    latitudes: impl Iterator<Item = &'b f64>,
+    longitudes: impl Iterator<Item = &'b f64>,
+) -> Vec<Option<&'a str>> {
+    latitudes
+        .zip(longitudes)
+        .map(|(&latitude, &longitude)| {
+            let position = LatLon::new(latitude, longitude).ok()?;
+            boundaries.ids(position).first().copied()
+        })
+        .collect()
+}
+
+fn to_latlon(latitude: f64, longitude: f64) -> PyResult<LatLon> {
+    LatLon::new(latitude, longitude).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+#[pymodule]
+#[pyo3(name = "country_boundaries")]
+fn country_boundaries_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<PyCountryBoundaries>()?;
+    m.add_function(wrap_pyfunction!(ids, m)?)?;
+    // © OpenStreetMap contributors, licensed under the ODbL
+    m.add(
+        "BOUNDARIES_ODBL_360X180",
+        PyBytes::new(py, BOUNDARIES_ODBL_360X180),
+    )?;
+    m.add(
+        "BOUNDARIES_ODBL_180X90",
+        PyBytes::new(py, BOUNDARIES_ODBL_180X90),
+    )?;
+    m.add(
+        "BOUNDARIES_ODBL_60X30",
+        PyBytes::new(py, BOUNDARIES_ODBL_60X30),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primary_ids_of_positions() {
+        let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180).unwrap();
+        let latitudes = [33.0, 10.0, 91.0, f64::NAN, 52.5];
+        let longitudes = [-97.0, -30.0, 0.0, 0.0, 13.4];
+        assert_eq!(
+            vec![Some("US-TX"), None, None, None, Some("DE")],
+            primary_ids(&boundaries, latitudes.iter(), longitudes.iter())
+        );
+    }
+}
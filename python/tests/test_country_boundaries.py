@@ -0,0 +1,76 @@
+import numpy as np
+import pytest
+
+import country_boundaries
+from country_boundaries import CountryBoundaries
+
+
+@pytest.fixture(scope="module")
+def boundaries():
+    return CountryBoundaries(country_boundaries.BOUNDARIES_ODBL_360X180)
+
+
+def test_ids(boundaries):
+    assert boundaries.ids(33.0, -97.0) == ["US-TX", "US"]
+    assert boundaries.ids(10.0, -30.0) == []
+
+
+def test_ids_at_invalid_position(boundaries):
+    with pytest.raises(ValueError):
+        boundaries.ids(91.0, 0.0)
+
+
+def test_is_in(boundaries):
+    assert boundaries.is_in(33.0, -97.0, "US-TX")
+    assert not boundaries.is_in(33.0, -97.0, "DE")
+
+
+def test_all_ids(boundaries):
+    ids = boundaries.all_ids()
+    assert "DE" in ids
+    assert ids == sorted(ids)
+
+
+def test_load_invalid_data():
+    with pytest.raises(ValueError):
+        CountryBoundaries(b"abc")
+
+
+def test_embedded_data():
+    for data in [
+        country_boundaries.BOUNDARIES_ODBL_360X180,
+        country_boundaries.BOUNDARIES_ODBL_180X90,
+        country_boundaries.BOUNDARIES_ODBL_60X30,
+    ]:
+        assert CountryBoundaries(data).is_in(33.0, -97.0, "US")
+
+
+def test_primary_ids(boundaries):
+    latitudes = np.array([33.0, 10.0, 91.0, np.nan, 52.5])
+    longitudes = np.array([-97.0, -30.0, 0.0, 0.0, 13.4])
+    ids = boundaries.primary_ids(latitudes, longitudes)
+    assert ids.dtype == object
+    assert ids.tolist() == ["US-TX", None, None, None, "DE"]
+
+
+def test_primary_ids_of_non_contiguous_arrays(boundaries):
+    positions = np.array([[33.0, -97.0], [52.5, 13.4]])
+    ids = boundaries.primary_ids(positions[:, 0], positions[:, 1])
+    assert ids.tolist() == ["US-TX", "DE"]
+
+
+def test_primary_ids_of_arrays_of_different_length(boundaries):
+    with pytest.raises(ValueError):
+        boundaries.primary_ids(np.array([1.0, 2.0]), np.array([1.0]))
+
+
+def test_ids_of_arrays():
+    ids = country_boundaries.ids(np.array([33.0, 52.5]), np.array([-97.0, 13.4]))
+    assert ids.tolist() == ["US-TX", "DE"]
+
+
+def test_ids_in_pandas_pipeline():
+    pd = pytest.importorskip("pandas")
+    df = pd.DataFrame({"lat": [33.0, 52.5], "lon": [-97.0, 13.4]})
+    df["country"] = country_boundaries.ids(df["lat"].to_numpy(), df["lon"].to_numpy())
+    assert df["country"].tolist() == ["US-TX", "DE"]
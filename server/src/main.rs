@@ -0,0 +1,309 @@
+use country_boundaries::{BoundingBox, CountryBoundaries, LatLon, SharedBoundaries};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const USAGE: &str = "\
+Usage: country-boundaries-server [--listen <address>] <file.ser>
+
+Serves lookups in the given boundaries file as JSON over HTTP:
+
+  GET  /ids?lat=<lat>&lon=<lon>          ids of the regions at the given position
+  POST /batch                            ids for each position of a JSON array of
+                                         {\"latitude\": <lat>, \"longitude\": <lon>}
+  GET  /bbox?minlat=<minlat>&minlon=<minlon>&maxlat=<maxlat>&maxlon=<maxlon>
+                                         ids of the regions containing / intersecting
+                                         the given bounding box
+  GET  /health                           whether the service is up
+
+The file is reloaded on SIGHUP. If it cannot be read, the previous data stays in use.
+
+Options:
+  --listen <address>    address to listen on, default 127.0.0.1:8080
+  -h, --help            print this help
+";
+
+/// maximum size of the body of a request
+const MAX_BODY_SIZE: u64 = 16 * 1024 * 1024;
+
+const WORKERS: usize = 4;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(ServerError::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(ServerError::Failed(e)) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+enum ServerError {
+    Usage(String),
+    Failed(Box<dyn Error + Send + Sync>),
+}
+
+impl<E: Into<Box<dyn Error + Send + Sync>>> From<E> for ServerError {
+    fn from(error: E) -> Self {
+        Self::Failed(error.into())
+    }
+}
+
+fn run(args: &[String]) -> Result<(), ServerError> {
+    let mut listen = "127.0.0.1:8080";
+    let mut positional: Vec<&str> = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return Ok(());
+            }
+            "--listen" => match args.next() {
+                Some(address) => listen = address,
+                None => return Err(usage("--listen requires an address")),
+            },
+            _ => positional.push(arg),
+        }
+    }
+    let file = match positional.as_slice() {
+        [file] => file.to_string(),
+        [] => return Err(usage("no boundaries file given")),
+        _ => return Err(usage("too many arguments")),
+    };
+
+    let data = SharedBoundaries::new(load(&file)?);
+    #[cfg(unix)]
+    reload_on_sighup(file, data.clone())?;
+
+    let server = Arc::new(Server::http(listen)?);
+    match server.server_addr().to_ip() {
+        Some(address) => println!("listening on http://{address}"),
+        None => println!("listening on {listen}"),
+    }
+    io::stdout().flush()?;
+
+    let workers: Vec<_> = (0..WORKERS)
+        .map(|_| {
+            let server = server.clone();
+            let data = data.clone();
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    let boundaries = data.load();
+                    if let Err(e) = respond(&boundaries, request) {
+                        eprintln!("error: {e}");
+                    }
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn reload_on_sighup(file: String, data: SharedBoundaries) -> io::Result<()> {
+    let mut signals = signal_hook::iterator::Signals::new([signal_hook::consts::SIGHUP])?;
+    thread::spawn(move || {
+        for _ in signals.forever() {
+            match data.reload_from_file(&file) {
+                Ok(()) => eprintln!("reloaded {file}"),
+                Err(e) => eprintln!("error: unable to reload {file}: {e}"),
+            }
+        }
+    });
+    Ok(())
+}
+
+fn respond(boundaries: &CountryBoundaries, mut request: Request) -> io::Result<()> {
+    let mut body = String::new();
+    let read = request
+        .as_reader()
+        .take(MAX_BODY_SIZE + 1)
+        .read_to_string(&mut body);
+    let (status, json) = match read {
+        Ok(_) if body.len() as u64 > MAX_BODY_SIZE => (413, error("request body too large")),
+        Ok(_) => handle(boundaries, request.method(), request.url(), &body),
+        Err(e) => (400, error(&e.to_string())),
+    };
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(json.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    request.respond(response)
+}
+
+/// Returns the status code and the JSON response for the request with the given `method`,
+/// `url` and `body`
+fn handle(boundaries: &CountryBoundaries, method: &Method, url: &str, body: &str) -> (u16, Value) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let result = match (method, path) {
+        (Method::Get, "/health") => Ok(json!({ "status": "ok" })),
+        (Method::Get, "/ids") => ids(boundaries, query),
+        (Method::Post, "/batch") => batch(boundaries, body),
+        (Method::Get, "/bbox") => bbox(boundaries, query),
+        (_, "/health" | "/ids" | "/batch" | "/bbox") => {
+            return (405, error("method not allowed"));
+        }
+        _ => return (404, error("not found")),
+    };
+    match result {
+        Ok(json) => (200, json),
+        Err(message) => (400, error(&message)),
+    }
+}
+
+fn ids(boundaries: &CountryBoundaries, query: &str) -> Result<Value, String> {
+    let position =
+        LatLon::new(param(query, "lat")?, param(query, "lon")?).map_err(|e| e.to_string())?;
+    Ok(json!(boundaries.ids(position)))
+}
+
+fn batch(boundaries: &CountryBoundaries, body: &str) -> Result<Value, String> {
+    let positions: Vec<LatLon> = serde_json::from_str(body).map_err(|e| e.to_string())?;
+    let ids: Vec<Vec<&str>> = positions
+        .into_iter()
+        .map(|position| boundaries.ids(position))
+        .collect();
+    Ok(json!(ids))
+}
+
+fn bbox(boundaries: &CountryBoundaries, query: &str) -> Result<Value, String> {
+    let bounds = BoundingBox::new(
+        param(query, "minlat")?,
+        param(query, "minlon")?,
+        param(query, "maxlat")?,
+        param(query, "maxlon")?,
+    )
+    .map_err(|e| e.to_string())?;
+    let mut containing: Vec<&str> = boundaries.containing_ids(bounds).into_iter().collect();
+    let mut intersecting: Vec<&str> = boundaries.intersecting_ids(bounds).into_iter().collect();
+    containing.sort_unstable();
+    intersecting.sort_unstable();
+    Ok(json!({ "containing": containing, "intersecting": intersecting }))
+}
+
+/// Returns the number of the query parameter with the given `name`
+fn param(query: &str, name: &str) -> Result<f64, String> {
+    let value = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+        .ok_or_else(|| format!("missing parameter '{name}'"))?;
+    value
+        .parse()
+        .map_err(|_| format!("parameter '{name}': '{value}' is not a number"))
+}
+
+fn error(message: &str) -> Value {
+    json!({ "error": message })
+}
+
+fn load(file: &str) -> Result<CountryBoundaries, Box<dyn Error + Send + Sync>> {
+    Ok(CountryBoundaries::from_reader(BufReader::new(File::open(
+        file,
+    )?))?)
+}
+
+fn usage(message: &str) -> ServerError {
+    ServerError::Usage(String::from(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use country_boundaries::BOUNDARIES_ODBL_360X180;
+
+    fn get(url: &str) -> (u16, Value) {
+        let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180).unwrap();
+        handle(&boundaries, &Method::Get, url, "")
+    }
+
+    fn post(url: &str, body: &str) -> (u16, Value) {
+        let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180).unwrap();
+        handle(&boundaries, &Method::Post, url, body)
+    }
+
+    #[test]
+    fn health() {
+        assert_eq!((200, json!({ "status": "ok" })), get("/health"));
+    }
+
+    #[test]
+    fn ids() {
+        assert_eq!(
+            (200, json!(["US-TX", "US"])),
+            get("/ids?lat=33.0&lon=-97.0")
+        );
+        assert_eq!(
+            (200, json!(["US-TX", "US"])),
+            get("/ids?lon=-97&foo&lat=33")
+        );
+        assert_eq!((200, json!([])), get("/ids?lat=-40.0&lon=-120.0"));
+    }
+
+    #[test]
+    fn ids_with_invalid_parameters() {
+        assert_eq!(400, get("/ids?lat=91.0&lon=0.0").0);
+        assert_eq!(400, get("/ids?lat=a&lon=0.0").0);
+        assert_eq!(400, get("/ids?lat=1.0").0);
+        assert_eq!(400, get("/ids").0);
+    }
+
+    #[test]
+    fn batch() {
+        let body = r#"[{"latitude": 33.0, "longitude": -97.0}, {"latitude": 47.6973, "longitude": 8.6910}]"#;
+        assert_eq!(
+            (200, json!([["US-TX", "US"], ["DE"]])),
+            post("/batch", body)
+        );
+        assert_eq!((200, json!([])), post("/batch", "[]"));
+    }
+
+    #[test]
+    fn batch_with_invalid_body() {
+        assert_eq!(400, post("/batch", "").0);
+        assert_eq!(
+            400,
+            post("/batch", r#"[{"latitude": 91.0, "longitude": 0.0}]"#).0
+        );
+        assert_eq!(
+            400,
+            post("/batch", r#"{"latitude": 1.0, "longitude": 0.0}"#).0
+        );
+    }
+
+    #[test]
+    fn bbox() {
+        assert_eq!(
+            (200, json!({ "containing": ["RU"], "intersecting": ["RU"] })),
+            get("/bbox?minlat=66.0&minlon=178.0&maxlat=68.0&maxlon=-178.0")
+        );
+        assert_eq!(
+            400,
+            get("/bbox?minlat=68.0&minlon=178.0&maxlat=66.0&maxlon=-178.0").0
+        );
+        assert_eq!(400, get("/bbox?minlat=66.0&minlon=178.0").0);
+    }
+
+    #[test]
+    fn unknown_paths_and_methods() {
+        assert_eq!(404, get("/").0);
+        assert_eq!(404, get("/idss").0);
+        assert_eq!(405, post("/ids?lat=33.0&lon=-97.0", "").0);
+        assert_eq!(405, get("/batch").0);
+    }
+}
@@ -0,0 +1,173 @@
+use country_boundaries::{CountryBoundariesBuilder, LatLon, Multipolygon};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The server process, killed when dropped
+struct ServerProcess {
+    child: Child,
+    address: String,
+}
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn start(file: &Path) -> ServerProcess {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_country-boundaries-server"))
+        .args(["--listen", "127.0.0.1:0"])
+        .arg(file)
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let address = line
+        .trim()
+        .strip_prefix("listening on http://")
+        .unwrap_or_else(|| panic!("unexpected output: {line}"))
+        .to_string();
+    ServerProcess { child, address }
+}
+
+/// Sends the request and returns the status code and the body of the response
+fn request(server: &ServerProcess, method: &str, url: &str, body: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(&server.address).unwrap();
+    write!(
+        stream,
+        "{method} {url} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, body.to_string())
+}
+
+/// Writes boundaries in which the whole world is the region with the given `id`
+fn write_world(file: &Path, id: &str) {
+    let position = |latitude, longitude| LatLon::new(latitude, longitude).unwrap();
+    let world = Multipolygon {
+        outer: vec![vec![
+            position(-90.0, -180.0),
+            position(-90.0, 180.0),
+            position(90.0, 180.0),
+            position(90.0, -180.0),
+        ]],
+        inner: vec![],
+    };
+    let boundaries = CountryBoundariesBuilder::new(4, 2)
+        .unwrap()
+        .add(id, world)
+        .build();
+    boundaries
+        .write_to(std::fs::File::create(file).unwrap())
+        .unwrap();
+}
+
+fn temp_file(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+#[test]
+fn serve_requests() {
+    let file = Path::new(env!("CARGO_MANIFEST_DIR")).join("../data/boundaries360x180.ser");
+    let server = start(&file);
+
+    assert_eq!(
+        (200, r#"{"status":"ok"}"#.to_string()),
+        request(&server, "GET", "/health", "")
+    );
+    assert_eq!(
+        (200, r#"["US-TX","US"]"#.to_string()),
+        request(&server, "GET", "/ids?lat=33.0&lon=-97.0", "")
+    );
+    assert_eq!(
+        (200, r#"[["US-TX","US"],[]]"#.to_string()),
+        request(
+            &server,
+            "POST",
+            "/batch",
+            r#"[{"latitude":33.0,"longitude":-97.0},{"latitude":-40.0,"longitude":-120.0}]"#
+        )
+    );
+    assert_eq!(
+        (
+            200,
+            r#"{"containing":["RU"],"intersecting":["RU"]}"#.to_string()
+        ),
+        request(
+            &server,
+            "GET",
+            "/bbox?minlat=66.0&minlon=178.0&maxlat=68.0&maxlon=-178.0",
+            ""
+        )
+    );
+    assert_eq!(400, request(&server, "GET", "/ids?lat=91.0&lon=0.0", "").0);
+    assert_eq!(404, request(&server, "GET", "/", "").0);
+}
+
+#[test]
+fn fail_on_missing_file() {
+    let status = Command::new(env!("CARGO_BIN_EXE_country-boundaries-server"))
+        .args(["--listen", "127.0.0.1:0", "does-not-exist.ser"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(!status.success());
+}
+
+#[cfg(unix)]
+#[test]
+fn reload_on_sighup() {
+    let file = temp_file("reload.ser");
+    write_world(&file, "A");
+    let server = start(&file);
+    assert_eq!(
+        (200, r#"["A"]"#.to_string()),
+        request(&server, "GET", "/ids?lat=0.0&lon=0.0", "")
+    );
+
+    let hangup = || {
+        let status = Command::new("kill")
+            .args(["-HUP", &server.child.id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    let wait_for = |expected: &str| {
+        let start = Instant::now();
+        loop {
+            let (_, body) = request(&server, "GET", "/ids?lat=0.0&lon=0.0", "");
+            if body == expected {
+                break;
+            }
+            assert!(start.elapsed() < Duration::from_secs(10), "still {body}");
+            thread::sleep(Duration::from_millis(20));
+        }
+    };
+
+    write_world(&file, "B");
+    hangup();
+    wait_for(r#"["B"]"#);
+
+    // invalid data is not loaded, the previous data stays in use
+    std::fs::write(&file, [1, 2, 3]).unwrap();
+    hangup();
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(
+        (200, r#"["B"]"#.to_string()),
+        request(&server, "GET", "/ids?lat=0.0&lon=0.0", "")
+    );
+}
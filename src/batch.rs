@@ -0,0 +1,164 @@
+use crate::cell::Cell;
+use crate::multipolygon::Point;
+use crate::{CountryBoundaries, LatLon};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+impl CountryBoundaries {
+    /// Returns the ids of the regions each of the given `positions` is contained in, each
+    /// ordered by size of the region ascending. The result is in the same order as `positions`.
+    ///
+    /// Same as calling [`CountryBoundaries::ids`] for each position, but faster for a large
+    /// number of positions, because they are processed in blocks in which they are grouped by
+    /// the cell they are located in. With the `rayon` feature, the blocks are processed on
+    /// several threads.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// assert_eq!(
+    ///     vec![vec!["US-TX", "US"], vec!["DE"]],
+    ///     boundaries.ids_batch(&[LatLon::new(33.0, -97.0)?, LatLon::new(47.6973, 8.6910)?])
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ids_batch(&self, positions: &[LatLon]) -> Vec<Vec<&str>> {
+        self.batch(positions, |cell, point| {
+            self.region_ids_in_cell(cell, point)
+                .into_iter()
+                .map(|region| self.regions.id(region))
+                .collect()
+        })
+    }
+
+    /// Returns for each of the given `positions` whether it is in the region with the given
+    /// `id`. The result is in the same order as `positions`.
+    ///
+    /// Same as calling [`CountryBoundaries::is_in`] for each position, but faster for a large
+    /// number of positions, see [`CountryBoundaries::ids_batch`].
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// assert_eq!(
+    ///     vec![false, true],
+    ///     boundaries.is_in_batch(&[LatLon::new(33.0, -97.0)?, LatLon::new(47.6973, 8.6910)?], "DE")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_in_batch(&self, positions: &[LatLon], id: &str) -> Vec<bool> {
+        let Some(region) = self.region_id(id) else {
+            return vec![false; positions.len()];
+        };
+        self.batch(positions, |cell, point| cell.is_in(point, region))
+    }
+
+    /// Applies `query` to the cell and local point of each of the given `positions` and returns
+    /// the results in the order of `positions`.
+    fn batch<T, F>(&self, positions: &[LatLon], query: F) -> Vec<T>
+    where
+        T: Clone + Default + Send,
+        F: Fn(&Cell, Point) -> T + Sync,
+    {
+        #[cfg(not(feature = "rayon"))]
+        let results = positions
+            .chunks(BLOCK_SIZE)
+            .flat_map(|block| self.batch_block(block, &query))
+            .collect();
+        #[cfg(feature = "rayon")]
+        let results = positions
+            .par_chunks(BLOCK_SIZE)
+            .flat_map_iter(|block| self.batch_block(block, &query))
+            .collect();
+        results
+    }
+
+    /// Same as `batch`, but for one block of positions, which are grouped by cell so that each
+    /// cell only needs to be looked at once
+    fn batch_block<T, F>(&self, positions: &[LatLon], query: &F) -> Vec<T>
+    where
+        T: Clone + Default,
+        F: Fn(&Cell, Point) -> T,
+    {
+        let grid = self.grid();
+        let mut located: Vec<(usize, Point, usize)> = positions
+            .iter()
+            .enumerate()
+            .map(|(index, position)| {
+                let (x, y, point) = grid.cell_and_local_point(*position);
+                (y * self.raster_width + x, point, index)
+            })
+            .collect();
+        located.sort_unstable_by_key(|&(cell, _, _)| cell);
+
+        let mut results = vec![T::default(); positions.len()];
+        for group in located.chunk_by(|a, b| a.0 == b.0) {
+            let cell = &self.raster[group[0].0];
+            if cell.intersecting_areas.is_empty() {
+                // the result is the same for every point in the cell
+                let result = query(cell, group[0].1);
+                for &(_, _, index) in group {
+                    results[index] = result.clone();
+                }
+            } else {
+                for &(_, point, index) in group {
+                    results[index] = query(cell, point);
+                }
+            }
+        }
+        results
+    }
+}
+
+/// Number of positions that are grouped by cell at once. Small enough so that the results of one
+/// block stay in the CPU cache.
+const BLOCK_SIZE: usize = 16384;
+
+#[cfg(test)]
+mod tests {
+    use crate::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_60X30};
+
+    /// positions all over the world, more than fit into one block
+    fn positions() -> Vec<LatLon> {
+        let mut positions = Vec::new();
+        for latitude in (-90..=90).step_by(7) {
+            for longitude in (-180..=180).step_by(11) {
+                positions.push(LatLon::new(latitude as f64, longitude as f64).unwrap());
+            }
+        }
+        positions.into_iter().cycle().take(40_000).collect()
+    }
+
+    #[test]
+    fn ids_batch_returns_same_as_ids() {
+        let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_60X30).unwrap();
+        let positions = positions();
+        let expected: Vec<Vec<&str>> = positions.iter().map(|&p| boundaries.ids(p)).collect();
+        assert_eq!(expected, boundaries.ids_batch(&positions));
+    }
+
+    #[test]
+    fn is_in_batch_returns_same_as_is_in() {
+        let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_60X30).unwrap();
+        let positions = positions();
+        for id in ["RU", "US", "XX"] {
+            let expected: Vec<bool> = positions.iter().map(|&p| boundaries.is_in(p, id)).collect();
+            assert_eq!(expected, boundaries.is_in_batch(&positions, id));
+        }
+    }
+
+    #[test]
+    fn empty_batch() {
+        let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_60X30).unwrap();
+        assert!(boundaries.ids_batch(&[]).is_empty());
+        assert!(boundaries.is_in_batch(&[], "DE").is_empty());
+    }
+}
@@ -0,0 +1,449 @@
+use crate::grid::Grid;
+use crate::multipolygon::{is_point_in_ring, Point};
+use crate::{BoundingBox, LatLon, ReadError};
+use std::collections::{HashMap, HashSet};
+
+type Result<T> = std::result::Result<T, ReadError>;
+
+/// A view on the bytes of a country boundaries file that can be queried directly, without
+/// deserializing the data first.
+///
+/// It offers the same queries as [`CountryBoundaries`](crate::CountryBoundaries). Creating it
+/// is much faster than [`CountryBoundaries::from_reader`](crate::CountryBoundaries::from_reader)
+/// because only an index of where each cell of the raster starts is built, while queries are
+/// a bit slower. This makes it suitable for short-lived processes that only do a few queries.
+///
+/// The bytes may come from anywhere, e.g. from one of the included `BOUNDARIES_ODBL_*` statics
+/// or from a memory-mapped file.
+///
+/// # Example
+/// ```
+/// # use country_boundaries::{CountryBoundariesRef, LatLon, BOUNDARIES_ODBL_360X180};
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let boundaries = CountryBoundariesRef::from_bytes(BOUNDARIES_ODBL_360X180)?;
+/// assert_eq!(
+///     vec!["US-TX", "US"],
+///     boundaries.ids(LatLon::new(33.0, -97.0)?)
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CountryBoundariesRef<'a> {
+    /// the whole data
+    data: &'a [u8],
+    /// offsets within `data` at which each cell of the 2-dimensional array of cells starts
+    cell_offsets: Vec<usize>,
+    /// width of the raster
+    raster_width: usize,
+    /// the sizes of the different countries contained
+    geometry_sizes: HashMap<&'a str, f64>,
+}
+
+impl<'a> CountryBoundariesRef<'a> {
+    /// Create a `CountryBoundariesRef` from the bytes of a country boundaries file.
+    ///
+    /// # Errors
+    /// Returns an error if the given data is not a valid country boundaries file.
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self> {
+        let mut cursor = Cursor::new(data);
+        let version = cursor.u16().ok_or_else(eof)?;
+        if version != 2 {
+            return Err(ReadError::WrongVersionNumber {
+                expected: 2,
+                actual: version,
+            });
+        }
+
+        let geometry_sizes_count = cursor.usize32()?;
+        let mut geometry_sizes = HashMap::with_capacity(geometry_sizes_count);
+        for _ in 0..geometry_sizes_count {
+            let id = cursor.string()?;
+            let size = cursor.f64().ok_or_else(eof)?;
+            geometry_sizes.insert(id, size);
+        }
+
+        let raster_width = cursor.usize32()?;
+
+        let raster_size = cursor.usize32()?;
+        if raster_width == 0 || raster_size < raster_width || raster_size % raster_width != 0 {
+            return Err(ReadError::InvalidData);
+        }
+        // every cell takes at least one byte, so a corrupt raster size can't allocate more
+        let mut cell_offsets = Vec::with_capacity(raster_size.min(data.len()));
+        for _ in 0..raster_size {
+            cell_offsets.push(cursor.offset);
+            skip_cell(&mut cursor)?;
+        }
+
+        Ok(Self {
+            data,
+            cell_offsets,
+            raster_width,
+            geometry_sizes,
+        })
+    }
+
+    /// Returns whether the given `position` is in the region with the given `id`
+    pub fn is_in(&self, position: LatLon, id: &str) -> bool {
+        let (cell, point) = self.cell_and_local_point(position);
+        cell.containing_ids().any(|cid| cid == id)
+            || cell
+                .intersecting_areas()
+                .any(|(aid, area)| aid == id && covers(area, point))
+    }
+
+    /// Returns whether the given `position` is in any of the regions with the given `ids`.
+    pub fn is_in_any(&self, position: LatLon, ids: &HashSet<&str>) -> bool {
+        let (cell, point) = self.cell_and_local_point(position);
+        cell.containing_ids().any(|cid| ids.contains(cid))
+            || cell
+                .intersecting_areas()
+                .any(|(aid, area)| ids.contains(aid) && covers(area, point))
+    }
+
+    /// Returns the ids of the regions the given `position` is contained in, ordered by size of
+    /// the region ascending
+    pub fn ids(&self, position: LatLon) -> Vec<&'a str> {
+        let (cell, point) = self.cell_and_local_point(position);
+        let mut result: Vec<&'a str> = cell
+            .intersecting_areas()
+            .filter(|(_, area)| covers(area, point))
+            .map(|(id, _)| id)
+            .chain(cell.containing_ids())
+            .collect();
+        let zero = 0.0;
+        result.sort_by(|&a, &b| {
+            let a = self.geometry_sizes.get(a).unwrap_or(&zero);
+            let b = self.geometry_sizes.get(b).unwrap_or(&zero);
+            a.total_cmp(b)
+        });
+        result
+    }
+
+    /// Returns the ids of the regions that fully contain the given bounding box `bounds`.
+    ///
+    /// The given bounding box is allowed to wrap around the 180th longitude,
+    /// i.e `bounds.min_longitude` = 170 and `bounds.max_longitude` = -170 is fine.
+    pub fn containing_ids(&self, bounds: BoundingBox) -> HashSet<&'a str> {
+        let mut ids: HashSet<&'a str> = HashSet::new();
+        let mut first_cell = true;
+        for cell in self.cells(&bounds) {
+            if first_cell {
+                ids.extend(cell.containing_ids());
+                first_cell = false;
+            } else {
+                ids.retain(|&id| {
+                    cell.containing_ids()
+                        .any(|containing_id| containing_id == id)
+                });
+                if ids.is_empty() {
+                    return ids;
+                }
+            }
+        }
+        ids
+    }
+
+    /// Returns the ids of the regions that contain or at lest intersect with the given bounding box
+    /// `bounds`.
+    ///
+    /// The given bounding box is allowed to wrap around the 180th longitude,
+    /// i.e `bounds.min_longitude` = 170 and `bounds.max_longitude` = -170 is fine.
+    pub fn intersecting_ids(&self, bounds: BoundingBox) -> HashSet<&'a str> {
+        let mut ids: HashSet<&'a str> = HashSet::new();
+        for cell in self.cells(&bounds) {
+            ids.extend(cell.containing_ids());
+            ids.extend(cell.intersecting_areas().map(|(id, _)| id));
+        }
+        ids
+    }
+
+    fn cell_and_local_point(&self, position: LatLon) -> (CellRef<'a>, Point) {
+        let (x, y, point) = self.grid().cell_and_local_point(position);
+        (self.cell(x, y), point)
+    }
+
+    fn cell(&self, x: usize, y: usize) -> CellRef<'a> {
+        let offset = self.cell_offsets[y * self.raster_width + x];
+        CellRef {
+            data: &self.data[offset..],
+        }
+    }
+
+    fn cells(&self, bounds: &BoundingBox) -> impl Iterator<Item = CellRef<'a>> + '_ {
+        self.grid().cells(bounds).map(|(x, y)| self.cell(x, y))
+    }
+
+    fn grid(&self) -> Grid {
+        Grid {
+            width: self.raster_width,
+            height: self.cell_offsets.len() / self.raster_width,
+        }
+    }
+}
+
+/// The bytes of one cell in the country boundaries grid, starting at the beginning of the cell.
+///
+/// The structure of the data has already been validated when the `CountryBoundariesRef` was
+/// created, so reading it can not fail.
+#[derive(Debug, Copy, Clone)]
+struct CellRef<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> CellRef<'a> {
+    /// Ids of areas that completely cover this cell
+    fn containing_ids(&self) -> impl Iterator<Item = &'a str> {
+        let mut cursor = Cursor::new(self.data);
+        let mut remaining = cursor.u8().unwrap_or(0);
+        std::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+            remaining -= 1;
+            cursor.string().ok()
+        })
+    }
+
+    /// Ids + bytes of the geometry of areas that only partly cover this cell
+    fn intersecting_areas(&self) -> impl Iterator<Item = (&'a str, &'a [u8])> {
+        let mut cursor = Cursor::new(self.data);
+        let mut remaining = 0;
+        if skip_containing_ids(&mut cursor).is_ok() {
+            remaining = cursor.u8().unwrap_or(0);
+        }
+        std::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+            remaining -= 1;
+            let id = cursor.string().ok()?;
+            let start = cursor.offset;
+            skip_polygons(&mut cursor).ok()?;
+            skip_polygons(&mut cursor).ok()?;
+            Some((id, &cursor.data[start..cursor.offset]))
+        })
+    }
+}
+
+/// Returns whether the area given as the bytes of its outer and inner polygons covers the given
+/// `point`
+fn covers(area: &[u8], point: Point) -> bool {
+    let mut cursor = Cursor::new(area);
+    let mut insides = 0;
+    for direction in [1, -1] {
+        let polygons_count = cursor.u8().unwrap_or(0);
+        for _ in 0..polygons_count {
+            let Ok(ring) = cursor.ring() else {
+                return false;
+            };
+            if is_point_in_ring(point, ring_points(ring)) {
+                insides += direction;
+            }
+        }
+    }
+    insides > 0
+}
+
+fn ring_points(ring: &[u8]) -> impl DoubleEndedIterator<Item = Point> + Clone + '_ {
+    ring.chunks_exact(4).map(|p| Point {
+        x: u16::from_be_bytes([p[0], p[1]]),
+        y: u16::from_be_bytes([p[2], p[3]]),
+    })
+}
+
+fn skip_cell(cursor: &mut Cursor) -> Result<()> {
+    skip_containing_ids(cursor)?;
+    let intersecting_areas_size = cursor.u8().ok_or_else(eof)?;
+    for _ in 0..intersecting_areas_size {
+        cursor.string()?;
+        skip_polygons(cursor)?;
+        skip_polygons(cursor)?;
+    }
+    Ok(())
+}
+
+fn skip_containing_ids(cursor: &mut Cursor) -> Result<()> {
+    let containing_ids_size = cursor.u8().ok_or_else(eof)?;
+    for _ in 0..containing_ids_size {
+        cursor.string()?;
+    }
+    Ok(())
+}
+
+fn skip_polygons(cursor: &mut Cursor) -> Result<()> {
+    let size = cursor.u8().ok_or_else(eof)?;
+    for _ in 0..size {
+        cursor.ring()?;
+    }
+    Ok(())
+}
+
+fn eof() -> ReadError {
+    ReadError::Io(std::io::ErrorKind::UnexpectedEof.into())
+}
+
+/// Reads big-endian values from a byte slice, advancing the offset
+#[derive(Debug, Clone)]
+struct Cursor<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        let end = self.offset.checked_add(length)?;
+        let bytes = self.data.get(self.offset..end)?;
+        self.offset = end;
+        Some(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(u8::from_be_bytes(self.take_array()?))
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.take_array()?))
+    }
+
+    fn f64(&mut self) -> Option<f64> {
+        Some(f64::from_be_bytes(self.take_array()?))
+    }
+
+    fn usize32(&mut self) -> Result<usize> {
+        let value = i32::from_be_bytes(self.take_array().ok_or_else(eof)?);
+        if value < 0 {
+            return Err(ReadError::InvalidData);
+        }
+        Ok(usize::try_from(value)?)
+    }
+
+    fn string(&mut self) -> Result<&'a str> {
+        let length = usize::from(self.u16().ok_or_else(eof)?);
+        let bytes = self.take(length).ok_or_else(eof)?;
+        std::str::from_utf8(bytes).map_err(|_| ReadError::InvalidData)
+    }
+
+    /// Returns the bytes of the points of a ring
+    fn ring(&mut self) -> Result<&'a [u8]> {
+        let size = self.usize32()?;
+        let length = size.checked_mul(4).ok_or(ReadError::InvalidData)?;
+        self.take(length).ok_or_else(eof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::latlon;
+
+    // 2x1 raster
+    const DATA: [u8; 64] = [
+        0x00, 0x02, // version number
+        0x00, 0x00, 0x00, 0x02, // geometry sizes map length
+        0x00, 0x01, 0x41, // "A"
+        0x40, 0x29, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 12.5
+        0x00, 0x01, 0x42, // "B"
+        0x40, 0x24, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 10.0
+        0x00, 0x00, 0x00, 0x02, // raster width
+        0x00, 0x00, 0x00, 0x02, // raster size
+        // cell 1
+        0x01, // cell containing ids length
+        0x00, 0x01, 0x41, // "A"
+        0x00, // intersecting areas length
+        // cell 2
+        0x00, // cell containing ids length
+        0x01, // intersecting areas length
+        0x00, 0x01, 0x42, // "B"
+        0x01, // outer polygons length
+        0x00, 0x00, 0x00, 0x03, // ring length
+        0x00, 0x00, 0x00, 0x00, // 0,0
+        0xff, 0xff, 0x00, 0x00, // 65535,0
+        0x00, 0x00, 0xff, 0xff, // 0,65535
+        0x00, // inner polygons length
+    ];
+
+    #[test]
+    fn query_data() {
+        let boundaries = CountryBoundariesRef::from_bytes(&DATA).unwrap();
+        assert_eq!(vec!["A"], boundaries.ids(latlon(0.0, -90.0)));
+        assert_eq!(vec!["B"], boundaries.ids(latlon(-80.0, 10.0)));
+        assert!(boundaries.ids(latlon(80.0, 170.0)).is_empty());
+
+        assert!(boundaries.is_in(latlon(0.0, -90.0), "A"));
+        assert!(!boundaries.is_in(latlon(0.0, -90.0), "B"));
+        assert!(boundaries.is_in(latlon(-80.0, 10.0), "B"));
+        assert!(!boundaries.is_in(latlon(80.0, 170.0), "B"));
+
+        assert!(boundaries.is_in_any(latlon(-80.0, 10.0), &HashSet::from(["A", "B"])));
+        assert!(!boundaries.is_in_any(latlon(80.0, 170.0), &HashSet::from(["A", "B"])));
+    }
+
+    #[test]
+    fn query_bounding_box() {
+        let boundaries = CountryBoundariesRef::from_bytes(&DATA).unwrap();
+        let bounds = BoundingBox::new(-10.0, -10.0, 10.0, 10.0).unwrap();
+        assert_eq!(
+            HashSet::from(["A", "B"]),
+            boundaries.intersecting_ids(bounds)
+        );
+        assert!(boundaries.containing_ids(bounds).is_empty());
+        let bounds = BoundingBox::new(-10.0, -20.0, 10.0, -10.0).unwrap();
+        assert_eq!(HashSet::from(["A"]), boundaries.containing_ids(bounds));
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        for i in 0..DATA.len() - 1 {
+            assert!(CountryBoundariesRef::from_bytes(&DATA[0..i]).is_err());
+        }
+    }
+
+    #[test]
+    fn invalid_data_is_an_error() {
+        let mut wrong_version = DATA;
+        wrong_version[1] = 0x03;
+        assert!(CountryBoundariesRef::from_bytes(&wrong_version).is_err());
+
+        let mut invalid_utf8 = DATA;
+        invalid_utf8[8] = 0xff;
+        assert!(CountryBoundariesRef::from_bytes(&invalid_utf8).is_err());
+
+        let mut negative_ring_length = DATA;
+        negative_ring_length[47] = 0xff;
+        assert!(CountryBoundariesRef::from_bytes(&negative_ring_length).is_err());
+    }
+
+    #[test]
+    fn invalid_raster_size_is_an_error() {
+        let mut zero_raster_width = DATA;
+        zero_raster_width[31] = 0x00;
+        assert!(CountryBoundariesRef::from_bytes(&zero_raster_width).is_err());
+
+        let mut raster_size_smaller_than_width = DATA;
+        raster_size_smaller_than_width[31] = 0x03;
+        assert!(CountryBoundariesRef::from_bytes(&raster_size_smaller_than_width).is_err());
+
+        let mut zero_raster_size = DATA;
+        zero_raster_size[35] = 0x00;
+        assert!(CountryBoundariesRef::from_bytes(&zero_raster_size).is_err());
+
+        let mut raster_size_not_multiple_of_width = DATA;
+        raster_size_not_multiple_of_width[35] = 0x03;
+        assert!(CountryBoundariesRef::from_bytes(&raster_size_not_multiple_of_width).is_err());
+
+        let mut huge_raster_size = DATA;
+        huge_raster_size[32] = 0x7f;
+        assert!(CountryBoundariesRef::from_bytes(&huge_raster_size).is_err());
+    }
+}
@@ -1,68 +1,69 @@
-use std::collections::HashSet;
-
 use crate::multipolygon::Multipolygon;
 use crate::multipolygon::Point;
+use crate::region::RegionId;
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 /// One cell in the country boundaries grid
 pub struct Cell {
     /// Areas that completely cover this cell
-    pub containing_ids: Vec<String>,
+    pub containing_ids: Vec<RegionId>,
     /// Id + Areas that only partly cover this cell
-    pub intersecting_areas: Vec<(String, Multipolygon)>,
+    pub intersecting_areas: Vec<(RegionId, Multipolygon<Point>)>,
 }
 
 impl Cell {
     /// Returns whether the given `position` is in the area with the given `id`
-    pub fn is_in(&self, point: Point, id: &str) -> bool {
-        self.containing_ids.iter().any(|cid| cid == id)
+    pub fn is_in(&self, point: Point, id: RegionId) -> bool {
+        self.containing_ids.contains(&id)
             || self
                 .intersecting_areas
                 .iter()
                 .any(|a| a.0 == id && a.1.covers(point))
     }
 
-    /// Returns whether the given position is in any area with the given `ids`
-    pub fn is_in_any(&self, point: Point, ids: &HashSet<&str>) -> bool {
-        self.containing_ids
-            .iter()
-            .any(|containing_id| ids.contains(containing_id.as_str()))
+    /// Returns whether the given position is in any area whose id matches `is_any_of`
+    pub fn is_in_any(&self, point: Point, is_any_of: impl Fn(RegionId) -> bool) -> bool {
+        self.containing_ids.iter().any(|&id| is_any_of(id))
             || self
                 .intersecting_areas
                 .iter()
-                .any(|a| ids.contains(a.0.as_str()) && a.1.covers(point))
+                .any(|a| is_any_of(a.0) && a.1.covers(point))
     }
 
     /// Return all ids of areas that cover the given `position` (in no particular order)
-    pub fn get_ids(&self, point: Point) -> Vec<&str> {
+    pub fn get_ids(&self, point: Point) -> Vec<RegionId> {
         self.intersecting_areas
             .iter()
             .filter(|a| a.1.covers(point))
-            .map(|a| a.0.as_str())
-            .chain(self.containing_ids.iter().map(String::as_str))
+            .map(|a| a.0)
+            .chain(self.containing_ids.iter().copied())
             .collect()
     }
 
     /// Return all ids of areas that completely cover or partly cover this cell
-    pub fn get_all_ids(&self) -> Vec<&str> {
+    pub fn get_all_ids(&self) -> impl Iterator<Item = RegionId> + '_ {
         self.containing_ids
             .iter()
-            .map(String::as_str)
-            .chain(self.intersecting_areas.iter().map(|a| a.0.as_str()))
-            .collect()
+            .copied()
+            .chain(self.intersecting_areas.iter().map(|a| a.0))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::region::RegionIdSet;
+
+    const A: RegionId = RegionId(0);
+    const B: RegionId = RegionId(1);
+    const C: RegionId = RegionId(2);
 
     #[test]
     fn get_definite_ids() {
         assert_eq!(
-            vec!["A", "C"],
+            vec![A, C],
             Cell {
-                containing_ids: vec![s("A"), s("C")],
+                containing_ids: vec![A, C],
                 intersecting_areas: vec![]
             }
             .get_ids(p(0, 0))
@@ -72,7 +73,7 @@ mod tests {
     #[test]
     fn get_in_geometry_ids() {
         assert_eq!(
-            vec!["B"],
+            vec![B],
             Cell {
                 containing_ids: vec![],
                 intersecting_areas: vec![b()]
@@ -94,9 +95,9 @@ mod tests {
     #[test]
     fn get_definite_and_in_geometry_ids() {
         assert_eq!(
-            vec!["B", "A"],
+            vec![B, A],
             Cell {
-                containing_ids: vec![s("A")],
+                containing_ids: vec![A],
                 intersecting_areas: vec![b()]
             }
             .get_ids(p(1, 1))
@@ -106,31 +107,43 @@ mod tests {
     #[test]
     fn get_ally_ids() {
         assert_eq!(
-            vec!["A", "B"],
+            vec![A, B],
             Cell {
-                containing_ids: vec![s("A")],
+                containing_ids: vec![A],
                 intersecting_areas: vec![b()]
             }
             .get_all_ids()
+            .collect::<Vec<_>>()
         );
     }
 
+    #[test]
+    fn is_in_definitely() {
+        let cell = Cell {
+            containing_ids: vec![A],
+            intersecting_areas: vec![b()],
+        };
+        assert!(cell.is_in(p(4, 4), A));
+        assert!(!cell.is_in(p(4, 4), B));
+        assert!(cell.is_in(p(1, 1), B));
+    }
+
     #[test]
     fn is_any_definitely() {
         assert!(Cell {
-            containing_ids: vec![s("A")],
+            containing_ids: vec![A],
             intersecting_areas: vec![]
         }
-        .is_in_any(p(0, 0), &HashSet::from(["B", "A"])));
+        .is_in_any(p(0, 0), |id| set(&[B, A]).contains(id)));
     }
 
     #[test]
     fn is_any_definitely_not() {
         assert!(!Cell {
-            containing_ids: vec![s("A")],
+            containing_ids: vec![A],
             intersecting_areas: vec![]
         }
-        .is_in_any(p(0, 0), &HashSet::from(["B"])));
+        .is_in_any(p(0, 0), |id| set(&[B]).contains(id)));
     }
 
     #[test]
@@ -139,7 +152,7 @@ mod tests {
             containing_ids: vec![],
             intersecting_areas: vec![b()]
         }
-        .is_in_any(p(1, 1), &HashSet::from(["B"])));
+        .is_in_any(p(1, 1), |id| set(&[B]).contains(id)));
     }
 
     #[test]
@@ -148,16 +161,16 @@ mod tests {
             containing_ids: vec![],
             intersecting_areas: vec![b()]
         }
-        .is_in_any(p(4, 4), &HashSet::from(["B"])));
+        .is_in_any(p(4, 4), |id| set(&[B]).contains(id)));
     }
 
-    fn s(val: &str) -> String {
-        String::from(val)
+    fn set(ids: &[RegionId]) -> RegionIdSet {
+        ids.iter().copied().collect()
     }
 
-    fn b() -> (String, Multipolygon) {
+    fn b() -> (RegionId, Multipolygon<Point>) {
         (
-            s("B"),
+            B,
             Multipolygon {
                 outer: vec![vec![p(0, 0), p(0, 2), p(2, 2), p(2, 0)]],
                 inner: vec![],
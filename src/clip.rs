@@ -0,0 +1,236 @@
+use crate::multipolygon::Point;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct XY {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl From<Point> for XY {
+    fn from(point: Point) -> Self {
+        XY {
+            x: point.x as f64,
+            y: point.y as f64,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum Edge {
+    Left(f64),
+    Right(f64),
+    Bottom(f64),
+    Top(f64),
+}
+
+impl Edge {
+    fn is_inside(&self, p: XY) -> bool {
+        match *self {
+            Edge::Left(x) => p.x >= x,
+            Edge::Right(x) => p.x <= x,
+            Edge::Bottom(y) => p.y >= y,
+            Edge::Top(y) => p.y <= y,
+        }
+    }
+
+    fn intersection(&self, a: XY, b: XY) -> XY {
+        match *self {
+            Edge::Left(x) | Edge::Right(x) => XY {
+                x,
+                y: a.y + (b.y - a.y) * (x - a.x) / (b.x - a.x),
+            },
+            Edge::Bottom(y) | Edge::Top(y) => XY {
+                x: a.x + (b.x - a.x) * (y - a.y) / (b.y - a.y),
+                y,
+            },
+        }
+    }
+}
+
+/// Clips the given ring against the half-plane defined by `edge` (Sutherland–Hodgman). For
+/// concave rings, the result may contain degenerate edges along `edge`, which however do not
+/// change which points are covered by the ring.
+pub fn clip_ring(ring: &[XY], edge: Edge) -> Vec<XY> {
+    let mut result = Vec::with_capacity(ring.len());
+    let Some(&last) = ring.last() else {
+        return result;
+    };
+    let mut a = last;
+    for &b in ring {
+        match (edge.is_inside(a), edge.is_inside(b)) {
+            (true, true) => result.push(b),
+            (true, false) => result.push(edge.intersection(a, b)),
+            (false, true) => {
+                result.push(edge.intersection(a, b));
+                result.push(b);
+            }
+            (false, false) => {}
+        }
+        a = b;
+    }
+    result
+}
+
+/// Signed area of the given ring (shoelace formula)
+pub fn area(ring: &[XY]) -> f64 {
+    let Some(&last) = ring.last() else {
+        return 0.0;
+    };
+    let mut a = last;
+    let mut sum = 0.0;
+    for &b in ring {
+        sum += (a.x - b.x) * (a.y + b.y);
+        a = b;
+    }
+    sum / 2.0
+}
+
+/// Clips the given ring to the rectangle from `min` to `max`
+pub fn clip_ring_to_rect(ring: &[XY], min: XY, max: XY) -> Vec<XY> {
+    let ring = clip_ring(ring, Edge::Left(min.x));
+    let ring = clip_ring(&ring, Edge::Right(max.x));
+    let ring = clip_ring(&ring, Edge::Bottom(min.y));
+    clip_ring(&ring, Edge::Top(max.y))
+}
+
+/// Returns the area of the intersection of the two given rings. The rings may be concave. The
+/// result is negative if the rings have a different orientation.
+pub fn intersection_area(a: &[XY], b: &[XY]) -> f64 {
+    let Some(&apex) = a.first() else {
+        return 0.0;
+    };
+    // Both rings are decomposed into fans of triangles with the same apex, each triangle is
+    // counted positive or negative depending on its orientation. The intersection area is then
+    // the sum of the intersection areas of all pairs of triangles, which are convex.
+    let fan = |ring: &[XY]| -> Vec<([XY; 3], f64)> {
+        let next = ring.iter().skip(1).chain(ring.first());
+        ring.iter()
+            .zip(next)
+            .filter_map(|(&p, &q)| {
+                let triangle = [apex, p, q];
+                let sign = area(&triangle).signum();
+                match sign {
+                    0.0 => None,
+                    1.0 => Some((triangle, sign)),
+                    _ => Some(([apex, q, p], sign)),
+                }
+            })
+            .collect()
+    };
+    let fan_a = fan(a);
+    let fan_b = fan(b);
+    let mut sum = 0.0;
+    for (triangle_a, sign_a) in &fan_a {
+        for (triangle_b, sign_b) in &fan_b {
+            let clipped = clip_ring_to_convex(triangle_a, triangle_b);
+            sum += sign_a * sign_b * area(&clipped);
+        }
+    }
+    sum
+}
+
+/// Clips the given ring to the given convex ring, which must be in counter-clockwise order
+fn clip_ring_to_convex(ring: &[XY], convex: &[XY]) -> Vec<XY> {
+    let mut result = ring.to_vec();
+    let next = convex.iter().skip(1).chain(convex.first());
+    for (&p, &q) in convex.iter().zip(next) {
+        // left of the line from p to q is inside
+        let side = |a: XY| (q.x - p.x) * (a.y - p.y) - (q.y - p.y) * (a.x - p.x);
+        let intersection = |a: XY, b: XY| {
+            let t = side(a) / (side(a) - side(b));
+            XY {
+                x: a.x + (b.x - a.x) * t,
+                y: a.y + (b.y - a.y) * t,
+            }
+        };
+        let ring = std::mem::take(&mut result);
+        let Some(&last) = ring.last() else {
+            break;
+        };
+        let mut a = last;
+        for &b in &ring {
+            match (side(a) >= 0.0, side(b) >= 0.0) {
+                (true, true) => result.push(b),
+                (true, false) => result.push(intersection(a, b)),
+                (false, true) => {
+                    result.push(intersection(a, b));
+                    result.push(b);
+                }
+                (false, false) => {}
+            }
+            a = b;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xy(x: f64, y: f64) -> XY {
+        XY { x, y }
+    }
+
+    #[test]
+    fn clip_ring_to_edge() {
+        let square = vec![xy(0.0, 0.0), xy(0.0, 2.0), xy(2.0, 2.0), xy(2.0, 0.0)];
+        assert_eq!(
+            vec![xy(1.0, 0.0), xy(1.0, 2.0), xy(2.0, 2.0), xy(2.0, 0.0)],
+            clip_ring(&square, Edge::Left(1.0))
+        );
+        assert!(clip_ring(&square, Edge::Left(3.0)).is_empty());
+        assert_eq!(square, clip_ring(&square, Edge::Top(2.0)));
+    }
+
+    #[test]
+    fn area_of_ring() {
+        let square = vec![xy(0.0, 0.0), xy(2.0, 0.0), xy(2.0, 2.0), xy(0.0, 2.0)];
+        assert_eq!(4.0, area(&square));
+        let reversed: Vec<XY> = square.into_iter().rev().collect();
+        assert_eq!(-4.0, area(&reversed));
+        assert_eq!(0.0, area(&[]));
+    }
+
+    #[test]
+    fn clip_ring_to_rectangle() {
+        let square = vec![xy(0.0, 0.0), xy(0.0, 4.0), xy(4.0, 4.0), xy(4.0, 0.0)];
+        let clipped = clip_ring_to_rect(&square, xy(1.0, 1.0), xy(2.0, 5.0));
+        assert_eq!(3.0, area(&clipped).abs());
+        assert!(clip_ring_to_rect(&square, xy(5.0, 5.0), xy(6.0, 6.0)).is_empty());
+    }
+
+    #[test]
+    fn intersection_area_of_rings() {
+        let square = vec![xy(0.0, 0.0), xy(4.0, 0.0), xy(4.0, 4.0), xy(0.0, 4.0)];
+        let shifted = vec![xy(2.0, 1.0), xy(6.0, 1.0), xy(6.0, 5.0), xy(2.0, 5.0)];
+        assert!((intersection_area(&square, &shifted) - 6.0).abs() < 1e-12);
+
+        let reversed: Vec<XY> = shifted.into_iter().rev().collect();
+        assert!((intersection_area(&square, &reversed) + 6.0).abs() < 1e-12);
+
+        let outside = vec![xy(5.0, 5.0), xy(6.0, 5.0), xy(6.0, 6.0)];
+        assert_eq!(0.0, intersection_area(&square, &outside));
+        assert_eq!(0.0, intersection_area(&square, &[]));
+    }
+
+    #[test]
+    fn intersection_area_of_concave_rings() {
+        // a U shape and a bar across its opening
+        let u = vec![
+            xy(0.0, 0.0),
+            xy(3.0, 0.0),
+            xy(3.0, 3.0),
+            xy(2.0, 3.0),
+            xy(2.0, 1.0),
+            xy(1.0, 1.0),
+            xy(1.0, 3.0),
+            xy(0.0, 3.0),
+        ];
+        let bar = vec![xy(-1.0, 2.0), xy(4.0, 2.0), xy(4.0, 2.5), xy(-1.0, 2.5)];
+        let result = intersection_area(&u, &bar);
+        assert!((result - 1.0).abs() < 1e-12, "{result}");
+        let result = intersection_area(&u, &u);
+        assert!((result - 7.0).abs() < 1e-12, "{result}");
+    }
+}
@@ -0,0 +1,188 @@
+use crate::cell::Cell;
+use crate::exact::covered_area;
+use crate::multipolygon::{Multipolygon, Point};
+use crate::polygon::covered_area_in_rings;
+use crate::region::RegionId;
+use crate::{BoundingBox, CountryBoundaries, LatLon};
+use std::collections::HashMap;
+
+impl CountryBoundaries {
+    /// Returns the ids of the regions that intersect with the given bounding box `bounds`
+    /// together with the fraction of the area of `bounds` that lies within each, from the
+    /// largest to the smallest fraction.
+    ///
+    /// The area is the area on the globe, i.e. parts of the bounding box nearer to the poles
+    /// count less than parts nearer to the equator.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BoundingBox, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // a bounding box around the Vaalserberg
+    /// let coverage = boundaries.coverage(BoundingBox::new(50.6, 5.9, 50.8, 6.1)?);
+    /// let (id, fraction) = coverage[0];
+    /// assert_eq!("BE", id);
+    /// assert!(fraction > 0.5 && fraction < 1.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn coverage(&self, bounds: BoundingBox) -> Vec<(&str, f64)> {
+        let parts = self
+            .cells_and_local_rects(bounds)
+            .map(|(y, cell, min, max)| {
+                let area = (max.x - min.x) * (max.y - min.y);
+                (y, cell, area, move |area: &_| covered_area(area, min, max))
+            });
+        self.coverage_of(parts)
+    }
+
+    /// Returns the ids of the regions that intersect with the given `polygon` together with the
+    /// fraction of the area of `polygon` that lies within each, from the largest to the
+    /// smallest fraction.
+    ///
+    /// See [`CountryBoundaries::containing_ids_polygon`] on how the polygon is given and
+    /// [`CountryBoundaries::coverage`] on how the area is measured.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // a triangle from Strasbourg to Kehl and Offenburg
+    /// let polygon = [
+    ///     LatLon::new(48.58, 7.75)?,
+    ///     LatLon::new(48.57, 7.82)?,
+    ///     LatLon::new(48.47, 7.94)?,
+    /// ];
+    /// let coverage = boundaries.coverage_polygon(&polygon);
+    /// assert_eq!(vec!["DE", "FR"], coverage.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn coverage_polygon(&self, polygon: &[LatLon]) -> Vec<(&str, f64)> {
+        self.coverage_rings(&[polygon])
+    }
+
+    /// Returns the coverage of the polygon with the given `rings`, the first being the outer
+    /// ring, the others holes
+    pub(crate) fn coverage_rings(&self, rings: &[&[LatLon]]) -> Vec<(&str, f64)> {
+        let parts = self
+            .cells_and_local_polygons(rings)
+            .into_iter()
+            .map(|local| {
+                let (y, cell, area) = (local.y, local.cell, local.area);
+                (y, cell, area, move |area: &_| {
+                    covered_area_in_rings(area, &local.rings)
+                })
+            });
+        self.coverage_of(parts)
+    }
+
+    /// Returns the coverage of the given parts of an area, each given as the row of the cell,
+    /// the cell, the area of the part in local coordinates and a function that returns how much
+    /// of it is covered by a multipolygon
+    fn coverage_of<'a, F: Fn(&Multipolygon<Point>) -> f64>(
+        &'a self,
+        parts: impl Iterator<Item = (usize, &'a Cell, f64, F)>,
+    ) -> Vec<(&'a str, f64)> {
+        let (cell_height, _) = self.grid().cell_size();
+        let mut total = 0.0;
+        let mut covered: HashMap<RegionId, f64> = HashMap::new();
+        for (y, cell, area, covered_area) in parts {
+            // the area of a cell on the globe is proportional to the difference of the sines of
+            // the latitudes of its top and bottom edge
+            let top = 90.0 - cell_height * y as f64;
+            let bottom = top - cell_height;
+            let weight = top.to_radians().sin() - bottom.to_radians().sin();
+            total += weight * area;
+            for &id in &cell.containing_ids {
+                *covered.entry(id).or_default() += weight * area;
+            }
+            for (id, multipolygon) in &cell.intersecting_areas {
+                let covered_area = covered_area(multipolygon).clamp(0.0, area);
+                if covered_area > 0.0 {
+                    *covered.entry(*id).or_default() += weight * covered_area;
+                }
+            }
+        }
+        if total <= 0.0 {
+            return Vec::new();
+        }
+        let mut result: Vec<(&str, f64)> = covered
+            .into_iter()
+            .map(|(id, area)| (self.regions.id(id), (area / total).min(1.0)))
+            .filter(|(_, fraction)| *fraction > 1e-9)
+            .collect();
+        result.sort_by(|(id_a, a), (id_b, b)| b.total_cmp(a).then(id_a.cmp(id_b)));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::{bbox, latlon, polygon, rectangle};
+    use crate::CountryBoundariesBuilder;
+
+    fn assert_coverage(expected: &[(&str, f64)], actual: &[(&str, f64)]) {
+        assert_eq!(expected.len(), actual.len(), "{actual:?}");
+        for ((expected_id, expected), (id, fraction)) in expected.iter().zip(actual) {
+            assert_eq!(expected_id, id, "{actual:?}");
+            assert!((expected - fraction).abs() < 1e-3, "{actual:?}");
+        }
+    }
+
+    #[test]
+    fn coverage_of_bounding_box() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(-5.0, 0.0, 5.0, 15.0)))
+            .add("B", polygon(rectangle(-5.0, 15.0, 5.0, 20.0)))
+            .build();
+
+        assert_coverage(
+            &[("A", 0.5), ("B", 0.25)],
+            &boundaries.coverage(bbox(-5.0, 5.0, 5.0, 25.0)),
+        );
+        assert_coverage(
+            &[("A", 1.0)],
+            &boundaries.coverage(bbox(-1.0, 1.0, 1.0, 2.0)),
+        );
+        assert!(boundaries.coverage(bbox(10.0, 10.0, 20.0, 20.0)).is_empty());
+    }
+
+    #[test]
+    fn coverage_of_polygon() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(-5.0, 0.0, 5.0, 15.0)))
+            .add("B", polygon(rectangle(-5.0, 15.0, 5.0, 20.0)))
+            .build();
+
+        // a triangle with half of its area left of 15° longitude, i.e. the part left of it is
+        // similar to the whole triangle with a factor of √½
+        let triangle = [
+            latlon(0.0, 17.0 - 2.0 / (1.0 - 0.5f64.sqrt())),
+            latlon(-2.0, 17.0),
+            latlon(2.0, 17.0),
+        ];
+        assert_coverage(
+            &[("A", 0.5), ("B", 0.5)],
+            &boundaries.coverage_polygon(&triangle),
+        );
+    }
+
+    #[test]
+    fn coverage_is_weighted_by_area_on_globe() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 60.0, 10.0)))
+            .build();
+
+        // the part between 0° and 30° latitude is larger than the part between 30° and 90°
+        let coverage = boundaries.coverage(bbox(0.0, 0.0, 90.0, 10.0));
+        assert_coverage(&[("A", 60f64.to_radians().sin())], &coverage);
+    }
+}
@@ -1,8 +1,8 @@
 use crate::cell::Cell;
 use crate::multipolygon::Multipolygon;
 use crate::multipolygon::Point;
+use crate::region::{RegionId, Regions};
 use crate::CountryBoundaries;
-use std::collections::HashMap;
 use std::fmt;
 use std::io::Read;
 
@@ -82,39 +82,43 @@ pub fn from_reader(mut reader: impl Read) -> Result<CountryBoundaries> {
     }
 
     let geometry_sizes_count = read_usize32(&mut reader)?;
-    let mut geometry_sizes = HashMap::with_capacity(geometry_sizes_count);
+    let mut regions = Regions::with_capacity(geometry_sizes_count);
     for _ in 0..geometry_sizes_count {
         let id = read_string(&mut reader)?;
         let size = read_f64(&mut reader)?;
-        geometry_sizes.insert(id, size);
+        let region = regions.insert(&id);
+        regions.set_size(region, size);
     }
 
     let raster_width = read_usize32(&mut reader)?;
 
     let raster_size = read_usize32(&mut reader)?;
+    if raster_width == 0 || raster_size < raster_width || raster_size % raster_width != 0 {
+        return Err(ReadError::InvalidData);
+    }
 
     let mut raster = Vec::with_capacity(raster_size);
     for _ in 0..raster_size {
-        raster.push(read_cell(&mut reader)?);
+        raster.push(read_cell(&mut reader, &mut regions)?);
     }
 
     Ok(CountryBoundaries {
         raster,
         raster_width,
-        geometry_sizes,
+        regions,
     })
 }
 
-fn read_cell(reader: &mut impl Read) -> Result<Cell> {
+fn read_cell(reader: &mut impl Read, regions: &mut Regions) -> Result<Cell> {
     let containing_ids_size = usize::from(read_u8(reader)?);
     let mut containing_ids = Vec::with_capacity(containing_ids_size);
     for _ in 0..containing_ids_size {
-        containing_ids.push(read_string(reader)?);
+        containing_ids.push(read_region_id(reader, regions)?);
     }
     let intersecting_areas_size = usize::from(read_u8(reader)?);
     let mut intersecting_areas = Vec::with_capacity(intersecting_areas_size);
     for _ in 0..intersecting_areas_size {
-        intersecting_areas.push(read_areas(reader)?);
+        intersecting_areas.push(read_areas(reader, regions)?);
     }
     Ok(Cell {
         containing_ids,
@@ -122,13 +126,20 @@ fn read_cell(reader: &mut impl Read) -> Result<Cell> {
     })
 }
 
-fn read_areas(reader: &mut impl Read) -> Result<(String, Multipolygon)> {
-    let id = read_string(reader)?;
+fn read_areas(
+    reader: &mut impl Read,
+    regions: &mut Regions,
+) -> Result<(RegionId, Multipolygon<Point>)> {
+    let id = read_region_id(reader, regions)?;
     let outer = read_polygons(reader)?;
     let inner = read_polygons(reader)?;
     Ok((id, Multipolygon { outer, inner }))
 }
 
+fn read_region_id(reader: &mut impl Read, regions: &mut Regions) -> Result<RegionId> {
+    Ok(regions.insert(&read_string(reader)?))
+}
+
 fn read_polygons(reader: &mut impl Read) -> Result<Vec<Vec<Point>>> {
     let size = usize::from(read_u8(reader)?);
     let mut polygons: Vec<Vec<Point>> = Vec::with_capacity(size);
@@ -356,7 +367,7 @@ mod tests {
                 containing_ids: vec![],
                 intersecting_areas: vec![]
             },
-            read_cell(&mut [0x00, 0x00].as_slice()).unwrap()
+            read_cell(&mut [0x00, 0x00].as_slice(), &mut Regions::default()).unwrap()
         );
 
         let cell = [
@@ -369,19 +380,24 @@ mod tests {
         for i in 0..cell.len() - 1 {
             assert!(read_polygons(&mut &cell[0..i]).is_err());
         }
+        let mut regions = Regions::default();
+        let b = regions.insert("B");
+        let cell = read_cell(&mut cell.as_slice(), &mut regions).unwrap();
+        let a = regions.get("A").unwrap();
         assert_eq!(
             Cell {
-                containing_ids: vec![String::from("A")],
+                containing_ids: vec![a],
                 intersecting_areas: vec![(
-                    String::from("B"),
+                    b,
                     Multipolygon {
                         inner: vec![],
                         outer: vec![]
                     }
                 )]
             },
-            read_cell(&mut cell.as_slice()).unwrap()
+            cell
         );
+        assert_eq!(2, regions.len());
     }
 
     #[test]
@@ -403,6 +419,34 @@ mod tests {
         assert!(from_reader(&mut data.as_slice()).is_err());
     }
 
+    #[test]
+    fn test_read_invalid_raster_width() {
+        for (raster_width, raster_size) in [(0, 0), (0, 1), (2, 1), (2, 3)] {
+            let mut data = vec![
+                0x00,
+                0x02, // version number
+                0x00,
+                0x00,
+                0x00,
+                0x00, // geometry sizes map length
+                0x00,
+                0x00,
+                0x00,
+                raster_width, // raster width
+                0x00,
+                0x00,
+                0x00,
+                raster_size, // raster size
+            ];
+            // empty cells
+            data.extend(std::iter::repeat_n(0x00, 2 * usize::from(raster_size)));
+            assert!(matches!(
+                from_reader(&mut data.as_slice()),
+                Err(ReadError::InvalidData)
+            ));
+        }
+    }
+
     #[test]
     fn test_read_negative_raster_size() {
         let data = [
@@ -435,17 +479,22 @@ mod tests {
         let minimum = [
             0x00, 0x02, // version number
             0x00, 0x00, 0x00, 0x00, // geometry sizes map length
-            0x00, 0x00, 0x00, 0x00, // raster width
-            0x00, 0x00, 0x00, 0x00, // raster size
+            0x00, 0x00, 0x00, 0x01, // raster width
+            0x00, 0x00, 0x00, 0x01, // raster size
+            0x00, // containing ids size
+            0x00, // intersecting areas size
         ];
         for i in 0..minimum.len() - 1 {
             assert!(from_reader(&mut &minimum[0..i]).is_err());
         }
         assert_eq!(
             CountryBoundaries {
-                raster: vec![],
-                raster_width: 0,
-                geometry_sizes: HashMap::new()
+                raster: vec![Cell {
+                    containing_ids: vec![],
+                    intersecting_areas: vec![],
+                }],
+                raster_width: 1,
+                regions: Regions::default(),
             },
             from_reader(&mut minimum.as_slice()).unwrap()
         );
@@ -467,14 +516,17 @@ mod tests {
         for i in 0..basic.len() - 1 {
             assert!(from_reader(&mut &basic[0..i]).is_err());
         }
+        let mut regions = Regions::default();
+        let a = regions.insert("A");
+        regions.set_size(a, 12.5);
         assert_eq!(
             CountryBoundaries {
                 raster: vec![Cell {
-                    containing_ids: vec![String::from("A")],
+                    containing_ids: vec![a],
                     intersecting_areas: vec![]
                 }],
                 raster_width: 1,
-                geometry_sizes: HashMap::from([(String::from("A"), 12.5)])
+                regions,
             },
             from_reader(&mut basic.as_slice()).unwrap()
         );
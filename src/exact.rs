@@ -0,0 +1,283 @@
+use crate::cell::Cell;
+use crate::clip::{area, clip_ring_to_rect, XY};
+use crate::grid::normalize;
+use crate::multipolygon::{Multipolygon, Point};
+use crate::region::RegionId;
+use crate::{BoundingBox, CountryBoundaries};
+use std::collections::HashSet;
+
+impl CountryBoundaries {
+    /// Returns the ids of the regions that fully contain the given bounding box `bounds`.
+    ///
+    /// Unlike [`CountryBoundaries::containing_ids`], which only returns regions that cover all
+    /// the cells the bounding box overlaps with, this checks the bounding box against the
+    /// actual geometry of the regions in those cells. It is therefore exact, but slower.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BoundingBox, BOUNDARIES_ODBL_360X180};
+    /// # use std::collections::HashSet;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // a bounding box around Aachen
+    /// let bounds = BoundingBox::new(50.75, 6.05, 50.8, 6.1)?;
+    /// assert!(boundaries.containing_ids(bounds).is_empty());
+    /// assert_eq!(HashSet::from(["DE"]), boundaries.containing_ids_exact(bounds));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn containing_ids_exact(&self, bounds: BoundingBox) -> HashSet<&str> {
+        let mut ids: Option<Vec<RegionId>> = None;
+        for (_, cell, min, max) in self.cells_and_local_rects(bounds) {
+            let rect_area = (max.x - min.x) * (max.y - min.y);
+            let covering = cell.containing_ids.iter().copied().chain(
+                cell.intersecting_areas
+                    .iter()
+                    .filter(|(_, area)| covered_area(area, min, max) >= rect_area * (1.0 - 1e-9))
+                    .map(|(id, _)| *id),
+            );
+            match &mut ids {
+                None => ids = Some(covering.collect()),
+                Some(ids) => {
+                    let covering: Vec<RegionId> = covering.collect();
+                    ids.retain(|id| covering.contains(id));
+                }
+            }
+            if ids.as_ref().is_some_and(Vec::is_empty) {
+                break;
+            }
+        }
+        ids.unwrap_or_default()
+            .into_iter()
+            .map(|id| self.regions.id(id))
+            .collect()
+    }
+
+    /// Returns the ids of the regions that contain or at least intersect with the given bounding
+    /// box `bounds`.
+    ///
+    /// Unlike [`CountryBoundaries::intersecting_ids`], which returns all regions found in the
+    /// cells the bounding box overlaps with, this checks the bounding box against the actual
+    /// geometry of the regions in those cells. It is therefore exact, but slower.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BoundingBox, BOUNDARIES_ODBL_360X180};
+    /// # use std::collections::HashSet;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // a bounding box around the Vaalserberg
+    /// let bounds = BoundingBox::new(50.75, 5.98, 50.76, 6.04)?;
+    /// assert_eq!(
+    ///     HashSet::from(["NL", "DE", "BE", "BE-WAL"]),
+    ///     boundaries.intersecting_ids_exact(bounds)
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn intersecting_ids_exact(&self, bounds: BoundingBox) -> HashSet<&str> {
+        let mut ids: HashSet<&str> = HashSet::new();
+        for (_, cell, min, max) in self.cells_and_local_rects(bounds) {
+            let rect_area = (max.x - min.x) * (max.y - min.y);
+            ids.extend(cell.containing_ids.iter().map(|&id| self.regions.id(id)));
+            ids.extend(
+                cell.intersecting_areas
+                    .iter()
+                    .filter(|(_, area)| covered_area(area, min, max) > rect_area * 1e-9)
+                    .map(|(id, _)| self.regions.id(*id)),
+            );
+        }
+        ids
+    }
+
+    /// Returns the row and the cell of each cell that overlaps with the given `bounds` together
+    /// with the part of `bounds` that lies within it, as a rectangle in local cell coordinates
+    pub(crate) fn cells_and_local_rects(
+        &self,
+        bounds: BoundingBox,
+    ) -> impl Iterator<Item = (usize, &Cell, XY, XY)> {
+        let grid = self.grid();
+        let (height, width) = grid.cell_size();
+        let min_longitude = normalize(bounds.min_longitude(), -180.0, 360.0);
+        let mut max_longitude = normalize(bounds.max_longitude(), -180.0, 360.0);
+        if max_longitude < min_longitude {
+            max_longitude += 360.0;
+        }
+        let min_latitude = bounds.min_latitude();
+        let max_latitude = bounds.max_latitude();
+
+        grid.cells(&bounds).filter_map(move |(x, y)| {
+            let (cell_latitude, cell_longitude) = grid.cell_origin(x, y);
+            let (min_y, max_y) = local_range(
+                (min_latitude - cell_latitude) / height,
+                (max_latitude - cell_latitude) / height,
+                min_latitude == max_latitude,
+            )?;
+            // the bounding box may extend beyond 180° longitude, so the cell might overlap with
+            // it only when shifted by 360°
+            let (min_x, max_x) = [0.0, 360.0]
+                .into_iter()
+                .filter_map(|shift| {
+                    local_range(
+                        (min_longitude - cell_longitude - shift) / width,
+                        (max_longitude - cell_longitude - shift) / width,
+                        min_longitude == max_longitude,
+                    )
+                })
+                .max_by(|(a0, a1), (b0, b1)| (a1 - a0).total_cmp(&(b1 - b0)))?;
+            Some((
+                y,
+                self.cell(x, y),
+                XY { x: min_x, y: min_y },
+                XY { x: max_x, y: max_y },
+            ))
+        })
+    }
+}
+
+/// Converts the range `start`..`end`, given relative to the cell, to local cell coordinates,
+/// clamped to the cell. Returns `None` if the range does not overlap with the cell. A
+/// `degenerate` range (i.e. a single value) is widened to one unit, so that it has an area.
+fn local_range(start: f64, end: f64, degenerate: bool) -> Option<(f64, f64)> {
+    let max = 0xffff as f64;
+    let start = (start * max).max(0.0);
+    let end = (end * max).min(max);
+    if degenerate {
+        if start > end {
+            return None;
+        }
+        let start = (start - 0.5).clamp(0.0, max - 1.0);
+        Some((start, start + 1.0))
+    } else if start < end {
+        Some((start, end))
+    } else {
+        None
+    }
+}
+
+/// Returns the area of the rectangle from `min` to `max` that is covered by the given
+/// multipolygon
+pub fn covered_area(multipolygon: &Multipolygon<Point>, min: XY, max: XY) -> f64 {
+    let clipped_area = |ring: &Vec<Point>| {
+        let ring: Vec<XY> = ring.iter().map(|&p| XY::from(p)).collect();
+        area(&clip_ring_to_rect(&ring, min, max)).abs()
+    };
+    multipolygon.outer.iter().map(clipped_area).sum::<f64>()
+        - multipolygon.inner.iter().map(clipped_area).sum::<f64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::{bbox, polygon, rectangle};
+    use crate::CountryBoundariesBuilder;
+    use std::collections::HashSet;
+
+    #[test]
+    fn containing_ids_exact_within_cell() {
+        let boundaries = CountryBoundariesBuilder::new(4, 2)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .build();
+
+        assert!(boundaries
+            .containing_ids(bbox(2.0, 2.0, 3.0, 3.0))
+            .is_empty());
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.containing_ids_exact(bbox(2.0, 2.0, 3.0, 3.0))
+        );
+        assert!(boundaries
+            .containing_ids_exact(bbox(5.0, 5.0, 20.0, 20.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn containing_ids_exact_across_cells() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 25.0, 25.0)))
+            .add("B", polygon(rectangle(0.0, 0.0, 15.0, 15.0)))
+            .build();
+
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.containing_ids_exact(bbox(5.0, 5.0, 20.0, 20.0))
+        );
+        assert_eq!(
+            HashSet::from(["A", "B"]),
+            boundaries.containing_ids_exact(bbox(5.0, 5.0, 14.0, 14.0))
+        );
+    }
+
+    #[test]
+    fn containing_ids_exact_wraps_longitude() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 175.0, 5.0, 185.0)))
+            .build();
+
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.containing_ids_exact(bbox(1.0, 178.0, 2.0, -178.0))
+        );
+        assert!(boundaries
+            .containing_ids_exact(bbox(1.0, 178.0, 2.0, -170.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn containing_ids_exact_of_point() {
+        let boundaries = CountryBoundariesBuilder::new(4, 2)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .build();
+
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.containing_ids_exact(bbox(5.0, 5.0, 5.0, 5.0))
+        );
+        assert!(boundaries
+            .containing_ids_exact(bbox(15.0, 5.0, 15.0, 5.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn intersecting_ids_exact() {
+        let boundaries = CountryBoundariesBuilder::new(4, 2)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .add("B", polygon(rectangle(0.0, 20.0, 10.0, 30.0)))
+            .build();
+
+        assert_eq!(
+            HashSet::from(["A", "B"]),
+            boundaries.intersecting_ids(bbox(20.0, 20.0, 30.0, 30.0))
+        );
+        assert!(boundaries
+            .intersecting_ids_exact(bbox(20.0, 20.0, 30.0, 30.0))
+            .is_empty());
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.intersecting_ids_exact(bbox(5.0, 5.0, 20.0, 15.0))
+        );
+        assert_eq!(
+            HashSet::from(["A", "B"]),
+            boundaries.intersecting_ids_exact(bbox(5.0, 5.0, 20.0, 25.0))
+        );
+    }
+
+    #[test]
+    fn intersecting_ids_exact_of_cell_covered_completely() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 30.0, 30.0)))
+            .build();
+
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.intersecting_ids_exact(bbox(12.0, 12.0, 18.0, 18.0))
+        );
+    }
+}
@@ -0,0 +1,344 @@
+use crate::geometry::{Vertex, CELL_SIZE};
+use crate::grid::normalize;
+use crate::multipolygon::{Multipolygon, Point};
+use crate::region::RegionId;
+use crate::{BoundingBox, CountryBoundaries, LatLon};
+
+impl CountryBoundaries {
+    /// Returns the bounding box of the region with the given `id`, or `None` if there is no such
+    /// region.
+    ///
+    /// For regions that cross the 180th meridian, the returned bounding box wraps around it, i.e.
+    /// its `min_longitude` is greater than its `max_longitude`.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// let germany = boundaries.bounds("DE").unwrap();
+    /// assert!(germany.min_longitude() < germany.max_longitude());
+    ///
+    /// let russia = boundaries.bounds("RU").unwrap();
+    /// assert!(russia.min_longitude() > russia.max_longitude());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bounds(&self, id: &str) -> Option<BoundingBox> {
+        let region = self.regions.get(id)?;
+        let (west, east) = self.longitude_range(region)?;
+        let (min_y, max_y) = self
+            .region_cells(region)
+            .map(|(origin, area)| {
+                let (min, max) = extent(area);
+                (origin.1 + min.1, origin.1 + max.1)
+            })
+            .reduce(|a, b| (a.0.min(b.0), a.1.max(b.1)))?;
+
+        let (cell_height, cell_width) = self.grid().cell_size();
+        let to_latitude =
+            |y: i64| (-90.0 + y as f64 * cell_height / CELL_SIZE as f64).clamp(-90.0, 90.0);
+        let to_longitude = |x: i64| -180.0 + x as f64 * cell_width / CELL_SIZE as f64;
+        let mut min_longitude = to_longitude(west);
+        let mut max_longitude = to_longitude(east);
+        if east - west < self.circumference() {
+            min_longitude = normalize(min_longitude, -180.0, 360.0);
+            max_longitude = normalize(max_longitude, -180.0, 360.0);
+            // keep the eastern edge of regions that end at the 180th meridian there
+            if max_longitude == -180.0 {
+                max_longitude = 180.0;
+            }
+        }
+        BoundingBox::new(
+            to_latitude(min_y),
+            min_longitude,
+            to_latitude(max_y),
+            max_longitude,
+        )
+        .ok()
+    }
+
+    /// Returns the centroid of the region with the given `id`, or `None` if there is no such
+    /// region.
+    ///
+    /// The centroid is the center of mass of the region's area in plate carrée projection. Note
+    /// that it is not necessarily within the region, e.g. for regions shaped like a crescent.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// let germany = boundaries.centroid("DE").unwrap();
+    /// assert!(boundaries.is_in(germany, "DE"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn centroid(&self, id: &str) -> Option<LatLon> {
+        let region = self.regions.get(id)?;
+        let (west, _) = self.longitude_range(region)?;
+        let circumference = self.circumference();
+
+        let mut area_sum = 0.0;
+        let mut x_sum = 0.0;
+        let mut y_sum = 0.0;
+        for (origin, area) in self.region_cells(region) {
+            // move the cell so that the region is continuous across the 180th meridian
+            let min_x = origin.0 + extent(area).0 .0;
+            let x = origin.0 + (min_x - west).rem_euclid(circumference) + west - min_x;
+            let (area, cx, cy) = match area {
+                None => {
+                    let size = (CELL_SIZE * CELL_SIZE) as f64;
+                    (size, CELL_SIZE as f64 / 2.0, CELL_SIZE as f64 / 2.0)
+                }
+                Some(area) => area_centroid(area),
+            };
+            area_sum += area;
+            x_sum += area * (x as f64 + cx);
+            y_sum += area * (origin.1 as f64 + cy);
+        }
+        if area_sum <= 0.0 {
+            return None;
+        }
+
+        let (cell_height, cell_width) = self.grid().cell_size();
+        let latitude = -90.0 + y_sum / area_sum * cell_height / CELL_SIZE as f64;
+        let longitude = -180.0 + x_sum / area_sum * cell_width / CELL_SIZE as f64;
+        LatLon::new(
+            latitude.clamp(-90.0, 90.0),
+            normalize(longitude, -180.0, 360.0),
+        )
+        .ok()
+    }
+
+    /// Returns the western and eastern edge of the given region in global raster coordinates.
+    /// The eastern edge is greater than the western edge, i.e. it is beyond the right edge of the
+    /// raster if the region crosses the 180th meridian.
+    fn longitude_range(&self, region: RegionId) -> Option<(i64, i64)> {
+        let mut ranges: Vec<(i64, i64)> = self
+            .region_cells(region)
+            .map(|(origin, area)| {
+                let (min, max) = extent(area);
+                (origin.0 + min.0, origin.0 + max.0)
+            })
+            .collect();
+        ranges.sort_unstable();
+
+        // merge overlapping ranges
+        let mut merged: Vec<(i64, i64)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        let first = *merged.first()?;
+        let last = *merged.last()?;
+
+        // the region spans everything except the largest gap between the ranges
+        let circumference = self.circumference();
+        let mut range = (first.0, last.1);
+        let mut largest_gap = first.0 + circumference - last.1;
+        for pair in merged.windows(2) {
+            let gap = pair[1].0 - pair[0].1;
+            if gap > largest_gap {
+                largest_gap = gap;
+                range = (pair[1].0, pair[0].1 + circumference);
+            }
+        }
+        Some(range)
+    }
+
+    /// Returns the width of the raster in global raster coordinates
+    fn circumference(&self) -> i64 {
+        self.raster_width as i64 * CELL_SIZE
+    }
+}
+
+/// Returns the bottom left and top right corner of the given area within a cell, or of the whole
+/// cell if there is no area
+fn extent(area: Option<&Multipolygon<Point>>) -> (Vertex, Vertex) {
+    let Some(area) = area else {
+        return ((0, 0), (CELL_SIZE, CELL_SIZE));
+    };
+    let mut min = (CELL_SIZE, CELL_SIZE);
+    let mut max = (0, 0);
+    for point in area.outer.iter().flatten() {
+        min = (min.0.min(point.x as i64), min.1.min(point.y as i64));
+        max = (max.0.max(point.x as i64), max.1.max(point.y as i64));
+    }
+    (min, max)
+}
+
+/// Returns the area and the centroid of the given multipolygon
+fn area_centroid(multipolygon: &Multipolygon<Point>) -> (f64, f64, f64) {
+    let mut area_sum = 0.0;
+    let mut x_sum = 0.0;
+    let mut y_sum = 0.0;
+    let rings = multipolygon
+        .outer
+        .iter()
+        .map(|ring| (ring, 1.0))
+        .chain(multipolygon.inner.iter().map(|ring| (ring, -1.0)));
+    for (ring, sign) in rings {
+        let (area, x, y) = ring_area_centroid(ring);
+        area_sum += sign * area;
+        x_sum += sign * area * x;
+        y_sum += sign * area * y;
+    }
+    if area_sum == 0.0 {
+        return (0.0, 0.0, 0.0);
+    }
+    (area_sum, x_sum / area_sum, y_sum / area_sum)
+}
+
+/// Returns the (unsigned) area and the centroid of the given ring
+fn ring_area_centroid(ring: &[Point]) -> (f64, f64, f64) {
+    let mut area = 0.0;
+    let mut x = 0.0;
+    let mut y = 0.0;
+    let next = ring.iter().skip(1).chain(ring.first());
+    for (a, b) in ring.iter().zip(next) {
+        let (ax, ay, bx, by) = (a.x as f64, a.y as f64, b.x as f64, b.y as f64);
+        let cross = ax * by - bx * ay;
+        area += cross;
+        x += (ax + bx) * cross;
+        y += (ay + by) * cross;
+    }
+    if area == 0.0 {
+        return (0.0, 0.0, 0.0);
+    }
+    // the signs cancel out for the centroid
+    ((area / 2.0).abs(), x / (3.0 * area), y / (3.0 * area))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::rectangle;
+    use crate::{CountryBoundariesBuilder, LatLon, Multipolygon};
+
+    fn polygons(outer: Vec<Vec<LatLon>>) -> Multipolygon<LatLon> {
+        Multipolygon {
+            outer,
+            inner: vec![],
+        }
+    }
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!((expected - actual).abs() < 1e-3, "{expected} != {actual}");
+    }
+
+    #[test]
+    fn bounds_and_centroid_of_unknown_region() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18).unwrap().build();
+        assert!(boundaries.bounds("A").is_none());
+        assert!(boundaries.centroid("A").is_none());
+    }
+
+    #[test]
+    fn bounds_and_centroid() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygons(vec![rectangle(12.0, 21.0, 31.0, 48.0)]))
+            .build();
+
+        let bounds = boundaries.bounds("A").unwrap();
+        assert_close(12.0, bounds.min_latitude());
+        assert_close(21.0, bounds.min_longitude());
+        assert_close(31.0, bounds.max_latitude());
+        assert_close(48.0, bounds.max_longitude());
+
+        let centroid = boundaries.centroid("A").unwrap();
+        assert_close(21.5, centroid.latitude());
+        assert_close(34.5, centroid.longitude());
+    }
+
+    #[test]
+    fn bounds_and_centroid_of_hole() {
+        let mut hole = rectangle(10.0, 10.0, 30.0, 20.0);
+        hole.reverse();
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(0.0, 0.0, 40.0, 40.0)],
+                    inner: vec![hole],
+                },
+            )
+            .build();
+
+        let bounds = boundaries.bounds("A").unwrap();
+        assert_close(0.0, bounds.min_longitude());
+        assert_close(40.0, bounds.max_longitude());
+
+        // 1600 * 20 - 200 * 15 = 1400 * x
+        let centroid = boundaries.centroid("A").unwrap();
+        assert_close(20.0, centroid.latitude());
+        assert_close(29000.0 / 1400.0, centroid.longitude());
+    }
+
+    #[test]
+    fn bounds_and_centroid_across_180th_meridian() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygons(vec![rectangle(0.0, 165.0, 5.0, 185.0)]))
+            .build();
+
+        let bounds = boundaries.bounds("A").unwrap();
+        assert_close(165.0, bounds.min_longitude());
+        assert_close(-175.0, bounds.max_longitude());
+
+        let centroid = boundaries.centroid("A").unwrap();
+        assert_close(2.5, centroid.latitude());
+        assert_close(175.0, centroid.longitude());
+    }
+
+    #[test]
+    fn bounds_of_separate_polygons_skip_largest_gap() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                polygons(vec![
+                    rectangle(0.0, -170.0, 5.0, -160.0),
+                    rectangle(0.0, 0.0, 5.0, 10.0),
+                    rectangle(0.0, 150.0, 5.0, 160.0),
+                ]),
+            )
+            .build();
+
+        // largest gap is from -160 to 0
+        let bounds = boundaries.bounds("A").unwrap();
+        assert_close(0.0, bounds.min_longitude());
+        assert_close(-160.0, bounds.max_longitude());
+    }
+
+    #[test]
+    fn bounds_of_region_ending_at_180th_meridian() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygons(vec![rectangle(0.0, 170.0, 5.0, 180.0)]))
+            .build();
+
+        let bounds = boundaries.bounds("A").unwrap();
+        assert_close(170.0, bounds.min_longitude());
+        assert_close(180.0, bounds.max_longitude());
+    }
+
+    #[test]
+    fn bounds_of_region_around_the_world() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygons(vec![rectangle(60.0, -180.0, 90.0, 180.0)]))
+            .build();
+
+        let bounds = boundaries.bounds("A").unwrap();
+        assert_close(60.0, bounds.min_latitude());
+        assert_close(90.0, bounds.max_latitude());
+        assert_close(-180.0, bounds.min_longitude());
+        assert_close(180.0, bounds.max_longitude());
+    }
+}
@@ -0,0 +1,438 @@
+use crate::cell::Cell;
+use crate::clip::{area, clip_ring, intersection_area, Edge, XY};
+use crate::multipolygon::{Multipolygon, Point};
+use crate::region::Regions;
+use crate::{CountryBoundaries, LatLon};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum BuildError {
+    InvalidRasterSize { width: usize, height: usize },
+}
+
+impl std::error::Error for BuildError {}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::InvalidRasterSize { width, height } => {
+                write!(
+                    f,
+                    "raster size {width}x{height} is invalid, width and height must be greater than 0"
+                )
+            }
+        }
+    }
+}
+
+/// Builds a `CountryBoundaries` from geometries given in latitude and longitude.
+///
+/// The geometries of all regions are sliced up into a raster of the given size: For each cell, it
+/// is determined which regions cover the cell completely and which only partly. Of the latter,
+/// the geometry clipped to the cell is stored, so that a point-in-polygon check only needs to be
+/// done on that little piece of geometry.
+///
+/// # Example
+/// ```
+/// # use country_boundaries::{CountryBoundariesBuilder, LatLon, Multipolygon};
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let square = vec![
+///     LatLon::new(0.0, 0.0)?,
+///     LatLon::new(0.0, 10.0)?,
+///     LatLon::new(10.0, 10.0)?,
+///     LatLon::new(10.0, 0.0)?,
+/// ];
+/// let boundaries = CountryBoundariesBuilder::new(360, 180)?
+///     .add("A", Multipolygon { outer: vec![square], inner: vec![] })
+///     .build();
+///
+/// assert_eq!(vec!["A"], boundaries.ids(LatLon::new(5.0, 5.0)?));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CountryBoundariesBuilder {
+    raster_width: usize,
+    raster_height: usize,
+    geometries: Vec<(String, Multipolygon<LatLon>)>,
+}
+
+impl CountryBoundariesBuilder {
+    /// Creates a new builder for a raster of the given size.
+    ///
+    /// # Errors
+    /// `raster_width` and `raster_height` must be greater than 0
+    pub fn new(raster_width: usize, raster_height: usize) -> Result<Self, BuildError> {
+        if raster_width == 0 || raster_height == 0 {
+            return Err(BuildError::InvalidRasterSize {
+                width: raster_width,
+                height: raster_height,
+            });
+        }
+        Ok(Self {
+            raster_width,
+            raster_height,
+            geometries: Vec::new(),
+        })
+    }
+
+    /// Adds the geometry of the region with the given `id`.
+    ///
+    /// Outer rings and inner rings (holes) may be in any orientation. If there is already a
+    /// geometry with the same `id`, the rings are added to it. Rings that cross the 180th
+    /// meridian must continue beyond ±180° longitude rather than jumping to the other side, i.e.
+    /// a ring from 170° to -170° longitude should be given as going from 170° to 190°.
+    pub fn add(&mut self, id: impl Into<String>, geometry: Multipolygon<LatLon>) -> &mut Self {
+        let id = id.into();
+        match self
+            .geometries
+            .iter_mut()
+            .find(|(other_id, _)| *other_id == id)
+        {
+            Some((_, existing)) => {
+                existing.outer.extend(geometry.outer);
+                existing.inner.extend(geometry.inner);
+            }
+            None => self.geometries.push((id, geometry)),
+        }
+        self
+    }
+
+    /// Slices the added geometries into a raster and returns the resulting `CountryBoundaries`.
+    pub fn build(&self) -> CountryBoundaries {
+        let mut raster = vec![Cell::default(); self.raster_width * self.raster_height];
+        let mut regions = Regions::with_capacity(self.geometries.len());
+
+        for (id, geometry) in &self.geometries {
+            let region = regions.insert(id);
+            let outer = to_xy_rings(&geometry.outer);
+            let inner = to_xy_rings(&geometry.inner);
+            let size = outer.iter().map(|r| area(r).abs()).sum::<f64>()
+                - inner.iter().map(|r| area(r).abs()).sum::<f64>();
+
+            for (index, piece) in self.slice(&outer, &inner) {
+                let cell = &mut raster[index];
+                if piece.covers_whole_cell() {
+                    cell.containing_ids.push(region);
+                } else if let Some(multipolygon) = piece.into_multipolygon() {
+                    cell.intersecting_areas.push((region, multipolygon));
+                }
+            }
+
+            regions.set_size(region, size);
+        }
+
+        CountryBoundaries {
+            raster,
+            raster_width: self.raster_width,
+            regions,
+        }
+    }
+
+    /// Clips the given rings to every cell they overlap with. Returns the index of each cell
+    /// together with the rings clipped to that cell, in local cell coordinates.
+    fn slice(&self, outer: &[Vec<XY>], inner: &[Vec<XY>]) -> Vec<(usize, Piece)> {
+        let cell_width = 360.0 / self.raster_width as f64;
+        let cell_height = 180.0 / self.raster_height as f64;
+
+        let mut pieces: HashMap<usize, Piece> = HashMap::new();
+        for (is_outer, rings) in [(true, outer), (false, inner)] {
+            for ring in rings {
+                let Some((min, max)) = bounds(ring) else {
+                    continue;
+                };
+                // cells are counted from the top (90° latitude) and from the left (-180° longitude)
+                let min_y = ((90.0 - max.y) / cell_height).floor().max(0.0) as usize;
+                let max_y =
+                    (((90.0 - min.y) / cell_height).ceil() as usize).min(self.raster_height);
+                // may be beyond the raster if the ring crosses the 180th meridian
+                let min_x = ((min.x + 180.0) / cell_width).floor() as i64;
+                let max_x = ((max.x + 180.0) / cell_width).ceil() as i64;
+
+                for y in min_y..max_y {
+                    let cell_min_lat = 90.0 - cell_height * (y + 1) as f64;
+                    let cell_max_lat = 90.0 - cell_height * y as f64;
+                    let row = clip_ring(ring, Edge::Bottom(cell_min_lat));
+                    let row = clip_ring(&row, Edge::Top(cell_max_lat));
+                    if row.len() < 3 {
+                        continue;
+                    }
+                    for x in min_x..max_x {
+                        let cell_min_lon = -180.0 + cell_width * x as f64;
+                        let cell_max_lon = cell_min_lon + cell_width;
+                        let clipped = clip_ring(&row, Edge::Left(cell_min_lon));
+                        let clipped = clip_ring(&clipped, Edge::Right(cell_max_lon));
+                        let clipped_area = area(&clipped).abs();
+                        if clipped_area <= 0.0 {
+                            continue;
+                        }
+                        let local_ring: Vec<XY> = clipped
+                            .iter()
+                            .map(|p| XY {
+                                x: (p.x - cell_min_lon) / cell_width,
+                                y: (p.y - cell_min_lat) / cell_height,
+                            })
+                            .collect();
+                        let index =
+                            y * self.raster_width + x.rem_euclid(self.raster_width as i64) as usize;
+                        let piece = pieces.entry(index).or_default();
+                        let relative_area = clipped_area / (cell_width * cell_height);
+                        if is_outer {
+                            piece.area += relative_area;
+                            piece.outer.push(local_ring);
+                        } else {
+                            piece.area -= relative_area;
+                            piece.inner.push(local_ring);
+                        }
+                    }
+                }
+            }
+        }
+        let mut pieces: Vec<(usize, Piece)> = pieces.into_iter().collect();
+        pieces.sort_by_key(|(index, _)| *index);
+        pieces
+    }
+}
+
+/// The part of a region's geometry within one cell. Coordinates are relative to the cell,
+/// i.e. from 0.0 to 1.0.
+#[derive(Debug, Default)]
+struct Piece {
+    outer: Vec<Vec<XY>>,
+    inner: Vec<Vec<XY>>,
+    /// covered area relative to the area of the cell
+    area: f64,
+}
+
+impl Piece {
+    /// Whether the rings cover the whole cell. The outer rings may overlap, e.g. if geometry was
+    /// added several times for the same id, so the sum of their areas is not enough: Only the
+    /// part that is certainly covered counts, i.e. the sum of the areas minus the areas in which
+    /// any two outer rings overlap.
+    fn covers_whole_cell(&self) -> bool {
+        if self.area < 1.0 - 1e-9 {
+            return false;
+        }
+        let mut overlap = 0.0;
+        for (i, a) in self.outer.iter().enumerate() {
+            for b in &self.outer[i + 1..] {
+                overlap += intersection_area(a, b).abs();
+            }
+        }
+        self.area - overlap >= 1.0 - 1e-9
+    }
+
+    fn into_multipolygon(self) -> Option<Multipolygon<Point>> {
+        if self.area <= 1e-12 {
+            return None;
+        }
+        let outer: Vec<Vec<Point>> = self.outer.iter().filter_map(|r| to_local_ring(r)).collect();
+        let inner: Vec<Vec<Point>> = self.inner.iter().filter_map(|r| to_local_ring(r)).collect();
+        if outer.is_empty() {
+            return None;
+        }
+        Some(Multipolygon { outer, inner })
+    }
+}
+
+fn bounds(ring: &[XY]) -> Option<(XY, XY)> {
+    let first = *ring.first()?;
+    Some(ring.iter().fold((first, first), |(min, max), p| {
+        (
+            XY {
+                x: min.x.min(p.x),
+                y: min.y.min(p.y),
+            },
+            XY {
+                x: max.x.max(p.x),
+                y: max.y.max(p.y),
+            },
+        )
+    }))
+}
+
+fn to_xy_rings(rings: &[Vec<LatLon>]) -> Vec<Vec<XY>> {
+    rings
+        .iter()
+        .map(|ring| {
+            ring.iter()
+                .map(|p| XY {
+                    x: p.longitude(),
+                    y: p.latitude(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Converts a ring with coordinates relative to the cell to a ring in local cell coordinates.
+/// Returns `None` if nothing remains of the ring after the conversion.
+fn to_local_ring(ring: &[XY]) -> Option<Vec<Point>> {
+    let mut result: Vec<Point> = Vec::with_capacity(ring.len());
+    for p in ring {
+        let point = Point {
+            x: (p.x * 0xffff as f64).round().clamp(0.0, 0xffff as f64) as u16,
+            y: (p.y * 0xffff as f64).round().clamp(0.0, 0xffff as f64) as u16,
+        };
+        if result.last() != Some(&point) {
+            result.push(point);
+        }
+    }
+    while result.len() > 1 && result.first() == result.last() {
+        result.pop();
+    }
+    if result.len() < 3 {
+        return None;
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::region::RegionId;
+    use crate::test_util::{latlon, polygon, rectangle};
+
+    #[test]
+    fn raster_size_must_not_be_zero() {
+        assert!(CountryBoundariesBuilder::new(0, 1).is_err());
+        assert!(CountryBoundariesBuilder::new(1, 0).is_err());
+        assert!(CountryBoundariesBuilder::new(1, 1).is_ok());
+    }
+
+    #[test]
+    fn cell_covered_completely_contains_id() {
+        // the world:
+        // ┌─┬─┐
+        // │A│ │
+        // ├─┼─┤
+        // │ │ │
+        // └─┴─┘
+        let boundaries = CountryBoundariesBuilder::new(2, 2)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, -180.0, 90.0, 0.0)))
+            .build();
+
+        assert_eq!(
+            vec!["A"],
+            ids(&boundaries, &boundaries.raster[0].containing_ids)
+        );
+        assert!(boundaries.raster[0].intersecting_areas.is_empty());
+        for cell in &boundaries.raster[1..] {
+            assert_eq!(&Cell::default(), cell);
+        }
+    }
+
+    #[test]
+    fn cell_covered_partly_intersects_id() {
+        let boundaries = CountryBoundariesBuilder::new(1, 1)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 90.0, 180.0)))
+            .build();
+
+        let cell = &boundaries.raster[0];
+        assert!(cell.containing_ids.is_empty());
+        assert_eq!(1, cell.intersecting_areas.len());
+        let (id, multipolygon) = &cell.intersecting_areas[0];
+        assert_eq!("A", boundaries.regions.id(*id));
+        assert_eq!(1, multipolygon.outer.len());
+        assert!(multipolygon.inner.is_empty());
+        assert!(multipolygon.covers(Point {
+            x: 0xc000,
+            y: 0xc000
+        }));
+        assert!(!multipolygon.covers(Point {
+            x: 0x4000,
+            y: 0x4000
+        }));
+    }
+
+    #[test]
+    fn cell_covered_by_several_polygons_contains_id() {
+        let boundaries = CountryBoundariesBuilder::new(1, 1)
+            .unwrap()
+            .add("A", polygon(rectangle(-90.0, -180.0, 0.0, 180.0)))
+            .add("A", polygon(rectangle(0.0, -180.0, 90.0, 180.0)))
+            .build();
+
+        assert_eq!(
+            vec!["A"],
+            ids(&boundaries, &boundaries.raster[0].containing_ids)
+        );
+        assert!(boundaries.raster[0].intersecting_areas.is_empty());
+    }
+
+    #[test]
+    fn cell_covered_by_overlapping_polygons_only_partly_intersects_id() {
+        // together, the polygons have more area than the cell, but they do not cover 120°-180°
+        let boundaries = CountryBoundariesBuilder::new(1, 1)
+            .unwrap()
+            .add("A", polygon(rectangle(-90.0, -180.0, 90.0, 60.0)))
+            .add("A", polygon(rectangle(-90.0, -120.0, 90.0, 120.0)))
+            .build();
+
+        assert!(boundaries.raster[0].containing_ids.is_empty());
+        assert_eq!(vec!["A"], boundaries.ids(latlon(0.0, 0.0)));
+        assert_eq!(vec!["A"], boundaries.ids(latlon(0.0, -150.0)));
+        assert!(boundaries.ids(latlon(0.0, 150.0)).is_empty());
+    }
+
+    #[test]
+    fn hole_is_not_covered() {
+        let boundaries = CountryBoundariesBuilder::new(4, 2)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(-90.0, -180.0, 90.0, 180.0)],
+                    inner: vec![rectangle(10.0, 10.0, 20.0, 20.0)],
+                },
+            )
+            .build();
+
+        assert_eq!(vec!["A"], boundaries.ids(latlon(5.0, 5.0)));
+        assert!(boundaries.ids(latlon(15.0, 15.0)).is_empty());
+        assert_eq!(vec!["A"], boundaries.ids(latlon(-45.0, -90.0)));
+    }
+
+    #[test]
+    fn geometry_wrapping_around_180th_meridian() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 175.0, 5.0, 185.0)))
+            .build();
+
+        assert_eq!(vec!["A"], boundaries.ids(latlon(2.0, 178.0)));
+        assert_eq!(vec!["A"], boundaries.ids(latlon(2.0, -178.0)));
+        assert!(boundaries.ids(latlon(2.0, 173.0)).is_empty());
+        assert!(boundaries.ids(latlon(2.0, -173.0)).is_empty());
+    }
+
+    #[test]
+    fn geometry_sizes_are_areas_in_degrees() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("B", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(0.0, 0.0, 2.0, 2.0)],
+                    inner: vec![rectangle(0.5, 0.5, 1.5, 1.5)],
+                },
+            )
+            .build();
+
+        let regions = &boundaries.regions;
+        assert_eq!(
+            vec![("B", 100.0), ("A", 3.0)],
+            regions.sizes().collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["A", "B"], boundaries.ids(latlon(0.1, 0.1)));
+    }
+
+    fn ids<'a>(boundaries: &'a CountryBoundaries, regions: &[RegionId]) -> Vec<&'a str> {
+        regions.iter().map(|&r| boundaries.regions.id(r)).collect()
+    }
+}
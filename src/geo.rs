@@ -0,0 +1,472 @@
+use crate::error::Error;
+use crate::{BoundingBox, CountryBoundaries, Crossing, LatLon, Multipolygon};
+use geo_types::{Coord, LineString, MultiPolygon, Point, Polygon, Rect};
+use std::collections::HashSet;
+
+impl From<LatLon> for Coord<f64> {
+    fn from(position: LatLon) -> Self {
+        Coord {
+            x: position.longitude(),
+            y: position.latitude(),
+        }
+    }
+}
+
+impl From<LatLon> for Point<f64> {
+    fn from(position: LatLon) -> Self {
+        Point(position.into())
+    }
+}
+
+impl TryFrom<Coord<f64>> for LatLon {
+    type Error = Error;
+
+    fn try_from(coord: Coord<f64>) -> Result<Self, Self::Error> {
+        LatLon::new(coord.y, coord.x)
+    }
+}
+
+impl TryFrom<Point<f64>> for LatLon {
+    type Error = Error;
+
+    fn try_from(point: Point<f64>) -> Result<Self, Self::Error> {
+        point.0.try_into()
+    }
+}
+
+/// A bounding box that wraps around the 180th meridian is converted to a `Rect` whose maximum
+/// longitude is beyond 180°, e.g. 170° to -170° becomes 170° to 190°.
+impl From<BoundingBox> for Rect<f64> {
+    fn from(bounds: BoundingBox) -> Self {
+        let mut max_longitude = bounds.max_longitude();
+        if bounds.min_longitude() > max_longitude {
+            max_longitude += 360.0;
+        }
+        Rect::new(
+            Coord {
+                x: bounds.min_longitude(),
+                y: bounds.min_latitude(),
+            },
+            Coord {
+                x: max_longitude,
+                y: bounds.max_latitude(),
+            },
+        )
+    }
+}
+
+impl TryFrom<Rect<f64>> for BoundingBox {
+    type Error = Error;
+
+    fn try_from(rect: Rect<f64>) -> Result<Self, Self::Error> {
+        BoundingBox::new(rect.min().y, rect.min().x, rect.max().y, rect.max().x)
+    }
+}
+
+/// Each hole is assigned to the smallest outer ring that contains it.
+impl From<Multipolygon<LatLon>> for MultiPolygon<f64> {
+    fn from(multipolygon: Multipolygon<LatLon>) -> Self {
+        let to_line_string =
+            |ring: Vec<LatLon>| -> LineString<f64> { ring.into_iter().map(Coord::from).collect() };
+        let mut polygons: Vec<(LineString<f64>, Vec<LineString<f64>>)> = multipolygon
+            .outer
+            .into_iter()
+            .map(|ring| (to_line_string(ring), Vec::new()))
+            .collect();
+        let areas: Vec<f64> = polygons
+            .iter()
+            .map(|(outer, _)| ring_area(&outer.0).abs())
+            .collect();
+
+        for hole in multipolygon.inner {
+            let hole = to_line_string(hole);
+            // the hole may touch its outer ring, so a ring that contains most of its points
+            // contains it. Of those, the smallest is the one it belongs to, as the outer rings
+            // may be nested, e.g. an island in a lake with a pond on it
+            let outer = polygons
+                .iter()
+                .enumerate()
+                .filter(|(_, (outer, _))| {
+                    let inside = hole.0.iter().filter(|&&p| is_in_ring(p, &outer.0)).count();
+                    2 * inside > hole.0.len()
+                })
+                .min_by(|(a, _), (b, _)| areas[*a].total_cmp(&areas[*b]));
+            if let Some((i, _)) = outer {
+                polygons[i].1.push(hole);
+            }
+        }
+        polygons
+            .into_iter()
+            .map(|(outer, inner)| Polygon::new(outer, inner))
+            .collect()
+    }
+}
+
+impl TryFrom<Polygon<f64>> for Multipolygon<LatLon> {
+    type Error = Error;
+
+    fn try_from(polygon: Polygon<f64>) -> Result<Self, Self::Error> {
+        let (exterior, interiors) = polygon.into_inner();
+        Ok(Multipolygon {
+            outer: vec![to_ring(&exterior)?],
+            inner: interiors.iter().map(to_ring).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<MultiPolygon<f64>> for Multipolygon<LatLon> {
+    type Error = Error;
+
+    fn try_from(multipolygon: MultiPolygon<f64>) -> Result<Self, Self::Error> {
+        let mut result = Multipolygon {
+            outer: Vec::new(),
+            inner: Vec::new(),
+        };
+        for polygon in multipolygon {
+            let polygon = Multipolygon::try_from(polygon)?;
+            result.outer.extend(polygon.outer);
+            result.inner.extend(polygon.inner);
+        }
+        Ok(result)
+    }
+}
+
+impl CountryBoundaries {
+    /// Returns the ids of the regions that fully contain the given `polygon`, see
+    /// [`CountryBoundaries::containing_ids_polygon`].
+    ///
+    /// # Errors
+    /// If any of the coordinates of the polygon is not a valid position.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// # use geo_types::polygon;
+    /// # use std::collections::HashSet;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // a triangle around Dallas
+    /// let polygon = polygon![(x: -97.2, y: 32.6), (x: -96.5, y: 32.6), (x: -96.8, y: 33.1)];
+    /// assert_eq!(
+    ///     HashSet::from(["US-TX", "US"]),
+    ///     boundaries.containing_ids_geo(&polygon)?
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn containing_ids_geo(&self, polygon: &Polygon<f64>) -> Result<HashSet<&str>, Error> {
+        let rings = to_rings(polygon)?;
+        let rings: Vec<&[LatLon]> = rings.iter().map(Vec::as_slice).collect();
+        Ok(self.containing_ids_rings(&rings))
+    }
+
+    /// Returns the ids of the regions that contain or at least intersect with the given
+    /// `polygon`, see [`CountryBoundaries::intersecting_ids_polygon`].
+    ///
+    /// # Errors
+    /// If any of the coordinates of the polygon is not a valid position.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// # use geo_types::polygon;
+    /// # use std::collections::HashSet;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // a triangle from Strasbourg to Kehl and Offenburg
+    /// let polygon = polygon![(x: 7.75, y: 48.58), (x: 7.82, y: 48.57), (x: 7.94, y: 48.47)];
+    /// assert_eq!(
+    ///     HashSet::from(["FR", "DE"]),
+    ///     boundaries.intersecting_ids_geo(&polygon)?
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn intersecting_ids_geo(&self, polygon: &Polygon<f64>) -> Result<HashSet<&str>, Error> {
+        let rings = to_rings(polygon)?;
+        let rings: Vec<&[LatLon]> = rings.iter().map(Vec::as_slice).collect();
+        Ok(self.intersecting_ids_rings(&rings))
+    }
+
+    /// Returns the ids of the regions that intersect with the given `polygon` together with the
+    /// fraction of its area that lies within each, see [`CountryBoundaries::coverage_polygon`].
+    ///
+    /// # Errors
+    /// If any of the coordinates of the polygon is not a valid position.
+    pub fn coverage_geo(&self, polygon: &Polygon<f64>) -> Result<Vec<(&str, f64)>, Error> {
+        let rings = to_rings(polygon)?;
+        let rings: Vec<&[LatLon]> = rings.iter().map(Vec::as_slice).collect();
+        Ok(self.coverage_rings(&rings))
+    }
+
+    /// Returns where the route along the given `line` enters and exits which regions, see
+    /// [`CountryBoundaries::ids_along`].
+    ///
+    /// # Errors
+    /// If any of the coordinates of the line is not a valid position.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// # use geo_types::line_string;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // from Strasbourg across the Rhine to Kehl
+    /// let line = line_string![(x: 7.75, y: 48.58), (x: 7.82, y: 48.57)];
+    /// let ids: Vec<&str> = boundaries
+    ///     .ids_along_geo(&line)?
+    ///     .into_iter()
+    ///     .map(|crossing| crossing.id)
+    ///     .collect();
+    /// assert_eq!(vec!["FR", "FR", "DE"], ids);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ids_along_geo(&self, line: &LineString<f64>) -> Result<Vec<Crossing<'_>>, Error> {
+        let path: Vec<LatLon> = line
+            .coords()
+            .map(|&coord| LatLon::try_from(coord))
+            .collect::<Result<_, _>>()?;
+        Ok(self.ids_along(&path))
+    }
+}
+
+/// Returns the rings of the given polygon, the exterior first
+fn to_rings(polygon: &Polygon<f64>) -> Result<Vec<Vec<LatLon>>, Error> {
+    std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .map(to_ring)
+        .collect()
+}
+
+/// Returns the positions of the given ring, without the closing position
+fn to_ring(ring: &LineString<f64>) -> Result<Vec<LatLon>, Error> {
+    let mut coords = ring.0.as_slice();
+    if coords.len() > 1 && coords.first() == coords.last() {
+        coords = &coords[..coords.len() - 1];
+    }
+    coords
+        .iter()
+        .map(|&coord| LatLon::try_from(coord))
+        .collect()
+}
+
+fn ring_area(ring: &[Coord<f64>]) -> f64 {
+    let next = ring.iter().skip(1).chain(ring.first());
+    ring.iter()
+        .zip(next)
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum::<f64>()
+        / 2.0
+}
+
+fn is_in_ring(p: Coord<f64>, ring: &[Coord<f64>]) -> bool {
+    let mut inside = false;
+    let previous = ring.iter().skip(ring.len().saturating_sub(1));
+    for (a, b) in previous.chain(ring.iter()).zip(ring.iter()) {
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{latlon, rectangle};
+    use crate::{CountryBoundariesBuilder, CrossingKind};
+    use geo_types::{line_string, polygon};
+
+    #[test]
+    fn convert_latlon() {
+        let point = Point::from(latlon(10.0, 20.0));
+        assert_eq!(Point::new(20.0, 10.0), point);
+        assert_eq!(latlon(10.0, 20.0), LatLon::try_from(point).unwrap());
+        assert!(LatLon::try_from(Point::new(0.0, 91.0)).is_err());
+    }
+
+    #[test]
+    fn convert_bounding_box() {
+        let bounds = BoundingBox::new(10.0, 20.0, 30.0, 40.0).unwrap();
+        let rect = Rect::from(bounds);
+        assert_eq!(Rect::new((20.0, 10.0), (40.0, 30.0)), rect);
+        let bounds = BoundingBox::try_from(rect).unwrap();
+        assert_eq!(
+            (10.0, 20.0, 30.0, 40.0),
+            (
+                bounds.min_latitude(),
+                bounds.min_longitude(),
+                bounds.max_latitude(),
+                bounds.max_longitude()
+            )
+        );
+    }
+
+    #[test]
+    fn convert_bounding_box_across_180th_meridian() {
+        let bounds = BoundingBox::new(10.0, 170.0, 30.0, -170.0).unwrap();
+        assert_eq!(Rect::new((170.0, 10.0), (190.0, 30.0)), Rect::from(bounds));
+    }
+
+    #[test]
+    fn convert_multipolygon() {
+        let multipolygon = Multipolygon {
+            outer: vec![
+                rectangle(0.0, 0.0, 10.0, 10.0),
+                rectangle(0.0, 20.0, 10.0, 30.0),
+                rectangle(2.0, 22.0, 8.0, 28.0),
+            ],
+            inner: vec![
+                rectangle(1.0, 21.0, 9.0, 29.0),
+                rectangle(1.0, 1.0, 2.0, 2.0),
+            ],
+        };
+        let converted = MultiPolygon::from(multipolygon.clone());
+        assert_eq!(3, converted.0.len());
+        assert_eq!(
+            vec![1, 1, 0],
+            converted
+                .iter()
+                .map(|polygon| polygon.interiors().len())
+                .collect::<Vec<_>>()
+        );
+        // the hole is in the outer ring it is in, not in the island within the hole
+        assert_eq!(
+            &LineString::from(vec![
+                (21.0, 1.0),
+                (29.0, 1.0),
+                (29.0, 9.0),
+                (21.0, 9.0),
+                (21.0, 1.0)
+            ]),
+            &converted.0[1].interiors()[0]
+        );
+
+        let mut back = Multipolygon::try_from(converted).unwrap();
+        back.inner
+            .sort_by(|a, b| a[0].longitude().total_cmp(&b[0].longitude()));
+        assert_eq!(multipolygon.outer, back.outer);
+        assert_eq!(
+            vec![
+                rectangle(1.0, 1.0, 2.0, 2.0),
+                rectangle(1.0, 21.0, 9.0, 29.0)
+            ],
+            back.inner
+        );
+    }
+
+    #[test]
+    fn convert_multipolygon_with_hole_in_nested_outer_rings() {
+        // an island in a lake with a pond on it that touches the shore of the island
+        let pond = vec![latlon(3.0, 3.0), latlon(3.0, 7.0), latlon(5.0, 8.0)];
+        let multipolygon = Multipolygon {
+            outer: vec![
+                rectangle(0.0, 0.0, 10.0, 10.0),
+                rectangle(2.0, 2.0, 8.0, 8.0),
+            ],
+            inner: vec![rectangle(1.0, 1.0, 9.0, 9.0), pond],
+        };
+        let converted = MultiPolygon::from(multipolygon);
+        assert_eq!(1, converted.0[0].interiors().len());
+        assert_eq!(
+            &LineString::from(vec![(3.0, 3.0), (7.0, 3.0), (8.0, 5.0), (3.0, 3.0)]),
+            &converted.0[1].interiors()[0]
+        );
+    }
+
+    #[test]
+    fn add_geometry_to_builder() {
+        let polygon = polygon![(x: 0.0, y: 0.0), (x: 10.0, y: 0.0), (x: 10.0, y: 10.0)];
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon.try_into().unwrap())
+            .build();
+        assert!(boundaries.is_in(latlon(1.0, 9.0), "A"));
+        assert!(!boundaries.is_in(latlon(9.0, 1.0), "A"));
+    }
+
+    #[test]
+    fn geometry_of_region() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(0.0, 0.0, 30.0, 30.0)],
+                    inner: vec![rectangle(10.0, 10.0, 20.0, 20.0)],
+                },
+            )
+            .build();
+        let geometry = MultiPolygon::from(boundaries.geometry("A").unwrap());
+        assert_eq!(1, geometry.0.len());
+        assert_eq!(1, geometry.0[0].interiors().len());
+    }
+
+    #[test]
+    fn queries_with_polygon_with_hole() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(0.0, 0.0, 30.0, 30.0)],
+                    inner: vec![],
+                },
+            )
+            .add(
+                "B",
+                Multipolygon {
+                    outer: vec![rectangle(12.0, 12.0, 18.0, 18.0)],
+                    inner: vec![],
+                },
+            )
+            .build();
+        let polygon = polygon!(
+            exterior: [(x: 5.0, y: 5.0), (x: 25.0, y: 5.0), (x: 25.0, y: 25.0), (x: 5.0, y: 25.0)],
+            interiors: [[(x: 10.0, y: 10.0), (x: 20.0, y: 10.0), (x: 20.0, y: 20.0), (x: 10.0, y: 20.0)]],
+        );
+
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.intersecting_ids_geo(&polygon).unwrap()
+        );
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.containing_ids_geo(&polygon).unwrap()
+        );
+        let coverage = boundaries.coverage_geo(&polygon).unwrap();
+        assert_eq!(1, coverage.len());
+        assert!((coverage[0].1 - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn ids_along_line_string() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(0.0, 0.0, 10.0, 10.0)],
+                    inner: vec![],
+                },
+            )
+            .build();
+        let crossings = boundaries
+            .ids_along_geo(&line_string![(x: 5.0, y: 5.0), (x: 15.0, y: 5.0)])
+            .unwrap();
+        assert_eq!(
+            vec![("A", CrossingKind::Enter), ("A", CrossingKind::Exit)],
+            crossings
+                .iter()
+                .map(|crossing| (crossing.id, crossing.kind))
+                .collect::<Vec<_>>()
+        );
+        assert!(boundaries
+            .ids_along_geo(&line_string![(x: 5.0, y: 95.0)])
+            .is_err());
+    }
+}
@@ -0,0 +1,296 @@
+use crate::error::Error;
+use crate::{CountryBoundariesBuilder, LatLon, Multipolygon};
+use serde_json::Value;
+use std::fmt;
+use std::io::Read;
+
+type Result<T> = std::result::Result<T, GeoJsonError>;
+
+#[derive(Debug)]
+pub enum GeoJsonError {
+    Json(serde_json::Error),
+    InvalidGeoJson(&'static str),
+    InvalidCoordinates(Error),
+}
+
+impl std::error::Error for GeoJsonError {}
+
+impl fmt::Display for GeoJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GeoJsonError::Json(e) => {
+                write!(f, "Unable to parse JSON: {}", e)
+            }
+            GeoJsonError::InvalidGeoJson(reason) => {
+                write!(f, "Invalid GeoJSON: {}", reason)
+            }
+            GeoJsonError::InvalidCoordinates(e) => {
+                write!(f, "Invalid coordinates: {}", e)
+            }
+        }
+    }
+}
+
+impl From<serde_json::Error> for GeoJsonError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl From<Error> for GeoJsonError {
+    fn from(error: Error) -> Self {
+        Self::InvalidCoordinates(error)
+    }
+}
+
+impl CountryBoundariesBuilder {
+    /// Adds the geometries of all features in the given GeoJSON to this builder.
+    ///
+    /// The GeoJSON may either be a `FeatureCollection` or a single `Feature`. The id of each
+    /// feature is taken from the property with the name `id_property`, e.g. `ISO3166-2`.
+    /// Features without such a property and features whose geometry is neither a `Polygon` nor a
+    /// `MultiPolygon` are ignored.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundariesBuilder, LatLon};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let geojson = r#"{
+    ///   "type": "FeatureCollection",
+    ///   "features": [{
+    ///     "type": "Feature",
+    ///     "properties": { "ISO3166-1": "XA" },
+    ///     "geometry": {
+    ///       "type": "Polygon",
+    ///       "coordinates": [[[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]]]
+    ///     }
+    ///   }]
+    /// }"#;
+    /// let boundaries = CountryBoundariesBuilder::new(360, 180)?
+    ///     .add_geojson(geojson.as_bytes(), "ISO3166-1")?
+    ///     .build();
+    ///
+    /// assert_eq!(vec!["XA"], boundaries.ids(LatLon::new(5.0, 5.0)?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the given data is not valid GeoJSON.
+    pub fn add_geojson(&mut self, reader: impl Read, id_property: &str) -> Result<&mut Self> {
+        let json: Value = serde_json::from_reader(reader)?;
+        match json["type"].as_str() {
+            Some("FeatureCollection") => {
+                let features = json["features"]
+                    .as_array()
+                    .ok_or(GeoJsonError::InvalidGeoJson("features must be an array"))?;
+                for feature in features {
+                    self.add_geojson_feature(feature, id_property)?;
+                }
+            }
+            Some("Feature") => {
+                self.add_geojson_feature(&json, id_property)?;
+            }
+            _ => {
+                return Err(GeoJsonError::InvalidGeoJson(
+                    "expected a FeatureCollection or a Feature",
+                ))
+            }
+        }
+        Ok(self)
+    }
+
+    fn add_geojson_feature(&mut self, feature: &Value, id_property: &str) -> Result<()> {
+        let id = match &feature["properties"][id_property] {
+            Value::String(id) => id.clone(),
+            Value::Number(id) => id.to_string(),
+            _ => return Ok(()),
+        };
+        let geometry = &feature["geometry"];
+        let coordinates = &geometry["coordinates"];
+        let multipolygon = match geometry["type"].as_str() {
+            Some("Polygon") => read_polygon(coordinates)?,
+            Some("MultiPolygon") => {
+                let mut multipolygon = Multipolygon {
+                    outer: vec![],
+                    inner: vec![],
+                };
+                for polygon in as_array(coordinates)? {
+                    let polygon = read_polygon(polygon)?;
+                    multipolygon.outer.extend(polygon.outer);
+                    multipolygon.inner.extend(polygon.inner);
+                }
+                multipolygon
+            }
+            _ => return Ok(()),
+        };
+        self.add(id, multipolygon);
+        Ok(())
+    }
+}
+
+fn read_polygon(value: &Value) -> Result<Multipolygon<LatLon>> {
+    let mut rings = as_array(value)?.iter();
+    let outer = match rings.next() {
+        Some(ring) => vec![read_ring(ring)?],
+        None => vec![],
+    };
+    let inner = rings.map(read_ring).collect::<Result<Vec<_>>>()?;
+    Ok(Multipolygon { outer, inner })
+}
+
+fn read_ring(value: &Value) -> Result<Vec<LatLon>> {
+    as_array(value)?.iter().map(read_position).collect()
+}
+
+fn read_position(value: &Value) -> Result<LatLon> {
+    match as_array(value)?.as_slice() {
+        [longitude, latitude, ..] => {
+            let longitude = longitude
+                .as_f64()
+                .ok_or(GeoJsonError::InvalidGeoJson("longitude must be a number"))?;
+            let latitude = latitude
+                .as_f64()
+                .ok_or(GeoJsonError::InvalidGeoJson("latitude must be a number"))?;
+            Ok(LatLon::new(latitude, longitude)?)
+        }
+        _ => Err(GeoJsonError::InvalidGeoJson(
+            "position must have at least two elements",
+        )),
+    }
+}
+
+fn as_array(value: &Value) -> Result<&Vec<Value>> {
+    value
+        .as_array()
+        .ok_or(GeoJsonError::InvalidGeoJson("coordinates must be arrays"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::latlon;
+
+    fn builder() -> CountryBoundariesBuilder {
+        CountryBoundariesBuilder::new(36, 18).unwrap()
+    }
+
+    #[test]
+    fn read_polygon_with_hole() {
+        let polygon = read_polygon(
+            &serde_json::from_str(
+                "[[[0, 0], [10, 0], [10, 10], [0, 0]], [[1, 1], [2, 1], [2, 2], [1, 1]]]",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![vec![
+                latlon(0.0, 0.0),
+                latlon(0.0, 10.0),
+                latlon(10.0, 10.0),
+                latlon(0.0, 0.0)
+            ]],
+            polygon.outer
+        );
+        assert_eq!(
+            vec![vec![
+                latlon(1.0, 1.0),
+                latlon(1.0, 2.0),
+                latlon(2.0, 2.0),
+                latlon(1.0, 1.0)
+            ]],
+            polygon.inner
+        );
+    }
+
+    #[test]
+    fn read_invalid_positions() {
+        assert!(read_position(&serde_json::from_str("[0]").unwrap()).is_err());
+        assert!(read_position(&serde_json::from_str("[0, \"a\"]").unwrap()).is_err());
+        assert!(read_position(&serde_json::from_str("[0, 91]").unwrap()).is_err());
+        assert!(read_position(&serde_json::from_str("[0, 1, 2]").unwrap()).is_ok());
+    }
+
+    #[test]
+    fn add_feature_collection() {
+        let geojson = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": { "id": "A" },
+                    "geometry": {
+                        "type": "MultiPolygon",
+                        "coordinates": [
+                            [[[0, 0], [10, 0], [10, 10], [0, 10]]],
+                            [[[20, 0], [30, 0], [30, 10], [20, 10]]]
+                        ]
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "properties": { "id": 1 },
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [[[0, 0], [30, 0], [30, 30], [0, 30]]]
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "properties": { "name": "no id" },
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [[[0, 0], [30, 0], [30, 30], [0, 30]]]
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "properties": { "id": "C" },
+                    "geometry": { "type": "Point", "coordinates": [0, 0] }
+                }
+            ]
+        }"#;
+        let boundaries = builder()
+            .add_geojson(geojson.as_bytes(), "id")
+            .unwrap()
+            .build();
+        assert_eq!(vec!["A", "1"], boundaries.ids(latlon(5.0, 5.0)));
+        assert_eq!(vec!["1"], boundaries.ids(latlon(5.0, 15.0)));
+        assert_eq!(vec!["A", "1"], boundaries.ids(latlon(5.0, 25.0)));
+        assert!(boundaries.ids(latlon(5.0, 35.0)).is_empty());
+    }
+
+    #[test]
+    fn add_single_feature() {
+        let geojson = r#"{
+            "type": "Feature",
+            "properties": { "id": "A" },
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [[[0, 0], [10, 0], [10, 10], [0, 10]]]
+            }
+        }"#;
+        let boundaries = builder()
+            .add_geojson(geojson.as_bytes(), "id")
+            .unwrap()
+            .build();
+        assert_eq!(vec!["A"], boundaries.ids(latlon(5.0, 5.0)));
+    }
+
+    #[test]
+    fn add_invalid_geojson() {
+        assert!(builder().add_geojson("".as_bytes(), "id").is_err());
+        assert!(builder().add_geojson("[]".as_bytes(), "id").is_err());
+        assert!(builder()
+            .add_geojson(
+                r#"{"type": "Point", "coordinates": [0, 0]}"#.as_bytes(),
+                "id"
+            )
+            .is_err());
+        assert!(builder()
+            .add_geojson(r#"{"type": "FeatureCollection"}"#.as_bytes(), "id")
+            .is_err());
+    }
+}
@@ -0,0 +1,424 @@
+use crate::multipolygon::Point;
+use crate::region::RegionId;
+use crate::{CountryBoundaries, LatLon, Multipolygon};
+use std::collections::BTreeMap;
+
+/// A point in global raster coordinates, i.e. the local coordinates of a cell offset by the
+/// position of the cell. x increases to the east, y increases to the north.
+pub type Vertex = (i64, i64);
+
+/// Size of a cell in local coordinates
+pub const CELL_SIZE: i64 = 0xffff;
+
+impl CountryBoundaries {
+    /// Returns the geometry of the region with the given `id` as it is used by this instance, or
+    /// `None` if there is no such region.
+    ///
+    /// The geometry is reassembled from the pieces of the region within each cell, the seams
+    /// between the cells are dissolved. Outer rings are returned in counter-clockwise, inner
+    /// rings in clockwise order. Geometry that crosses the 180th meridian is split there.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// let germany = boundaries.geometry("DE").unwrap();
+    /// // the mainland, Heligoland, Büsingen am Hochrhein, ...
+    /// assert!(germany.outer.len() > 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn geometry(&self, id: &str) -> Option<Multipolygon<LatLon>> {
+        let region = self.regions.get(id)?;
+        let grid = self.grid();
+
+        let mut edges: Vec<(Vertex, Vertex)> = Vec::new();
+        for (origin, area) in self.region_cells(region) {
+            let to_global = |p: &Point| (origin.0 + p.x as i64, origin.1 + p.y as i64);
+            match area {
+                None => {
+                    let square = [
+                        (0, 0),
+                        (CELL_SIZE, 0),
+                        (CELL_SIZE, CELL_SIZE),
+                        (0, CELL_SIZE),
+                    ];
+                    add_ring(
+                        &mut edges,
+                        square.map(|(x, y)| (origin.0 + x, origin.1 + y)),
+                        true,
+                    );
+                }
+                Some(area) => {
+                    for ring in &area.outer {
+                        add_ring(&mut edges, ring.iter().map(to_global), true);
+                    }
+                    for ring in &area.inner {
+                        add_ring(&mut edges, ring.iter().map(to_global), false);
+                    }
+                }
+            }
+        }
+
+        let (cell_height, cell_width) = grid.cell_size();
+        let to_latlon = |(x, y): Vertex| {
+            let latitude = -90.0 + y as f64 * cell_height / CELL_SIZE as f64;
+            let longitude = -180.0 + x as f64 * cell_width / CELL_SIZE as f64;
+            LatLon::new(latitude.clamp(-90.0, 90.0), longitude).expect("latitude is clamped")
+        };
+
+        let mut geometry = Multipolygon {
+            outer: Vec::new(),
+            inner: Vec::new(),
+        };
+        for ring in rings(dissolve(edges)) {
+            let is_outer = signed_area(&ring) > 0;
+            let ring = ring.into_iter().map(to_latlon).collect();
+            if is_outer {
+                geometry.outer.push(ring);
+            } else {
+                geometry.inner.push(ring);
+            }
+        }
+        Some(geometry)
+    }
+
+    /// Returns the origin of each cell the given `region` is in, in global raster coordinates,
+    /// together with the area of the region in that cell or `None` if it covers the whole cell
+    pub(crate) fn region_cells(
+        &self,
+        region: RegionId,
+    ) -> impl Iterator<Item = (Vertex, Option<&Multipolygon<Point>>)> {
+        let grid = self.grid();
+        self.raster.iter().enumerate().flat_map(move |(i, cell)| {
+            let origin = (
+                (i % grid.width) as i64 * CELL_SIZE,
+                (grid.height - 1 - i / grid.width) as i64 * CELL_SIZE,
+            );
+            let contains = cell
+                .containing_ids
+                .contains(&region)
+                .then_some((origin, None));
+            let areas = cell
+                .intersecting_areas
+                .iter()
+                .filter(move |(r, _)| *r == region)
+                .map(move |(_, area)| (origin, Some(area)));
+            contains.into_iter().chain(areas)
+        })
+    }
+}
+
+/// Adds the edges of the given ring to `edges`, oriented counter-clockwise if `is_outer` and
+/// clockwise otherwise, so that the area of the region is always on the left side of an edge
+fn add_ring(
+    edges: &mut Vec<(Vertex, Vertex)>,
+    ring: impl IntoIterator<Item = Vertex>,
+    is_outer: bool,
+) {
+    let mut ring: Vec<Vertex> = ring.into_iter().collect();
+    ring.dedup();
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    let area = signed_area(&ring);
+    if area == 0 {
+        return;
+    }
+    if (area > 0) != is_outer {
+        ring.reverse();
+    }
+    let next = ring.iter().skip(1).chain(ring.first());
+    edges.extend(ring.iter().copied().zip(next.copied()));
+}
+
+/// Returns the given edges without those that cancel each other out, i.e. the seams between
+/// pieces of the same region
+fn dissolve(edges: Vec<(Vertex, Vertex)>) -> Vec<(Vertex, Vertex)> {
+    // Edges on the cell borders are split at the vertices of all edges on the same border
+    // first, as the pieces on either side of a border don't necessarily have the same vertices
+    let mut breaks: BTreeMap<(bool, i64), Vec<i64>> = BTreeMap::new();
+    for &(a, b) in &edges {
+        if let Some((line, start, end)) = cell_border(a, b) {
+            breaks.entry(line).or_default().extend([start, end]);
+        }
+    }
+    for breaks in breaks.values_mut() {
+        breaks.sort_unstable();
+        breaks.dedup();
+    }
+
+    // count each edge in one direction positive, in the other negative
+    let mut counts: BTreeMap<(Vertex, Vertex), i32> = BTreeMap::new();
+    let mut count = |a: Vertex, b: Vertex| {
+        if a < b {
+            *counts.entry((a, b)).or_default() += 1;
+        } else {
+            *counts.entry((b, a)).or_default() -= 1;
+        }
+    };
+    for (a, b) in edges {
+        let Some(((vertical, line), start, end)) = cell_border(a, b) else {
+            count(a, b);
+            continue;
+        };
+        let vertex = |v: i64| if vertical { (line, v) } else { (v, line) };
+        let breaks = &breaks[&(vertical, line)];
+        let from = breaks.binary_search(&start.min(end)).unwrap();
+        let to = breaks.binary_search(&start.max(end)).unwrap();
+        for pair in breaks[from..=to].windows(2) {
+            if start < end {
+                count(vertex(pair[0]), vertex(pair[1]));
+            } else {
+                count(vertex(pair[1]), vertex(pair[0]));
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    for ((a, b), count) in counts {
+        let edge = if count > 0 { (a, b) } else { (b, a) };
+        for _ in 0..count.abs() {
+            result.push(edge);
+        }
+    }
+    result
+}
+
+/// Returns the border line (whether it is vertical and its x or y) and the start and end of the
+/// given edge along it, if the edge lies on a border between cells
+fn cell_border(a: Vertex, b: Vertex) -> Option<((bool, i64), i64, i64)> {
+    if a.0 == b.0 && a.0 % CELL_SIZE == 0 {
+        Some(((true, a.0), a.1, b.1))
+    } else if a.1 == b.1 && a.1 % CELL_SIZE == 0 {
+        Some(((false, a.1), a.0, b.0))
+    } else {
+        None
+    }
+}
+
+/// Connects the given edges to rings
+fn rings(edges: Vec<(Vertex, Vertex)>) -> Vec<Vec<Vertex>> {
+    let mut outgoing: BTreeMap<Vertex, Vec<Vertex>> = BTreeMap::new();
+    for (a, b) in edges {
+        outgoing.entry(a).or_default().push(b);
+    }
+
+    let mut rings = Vec::new();
+    while let Some(mut entry) = outgoing.first_entry() {
+        let start = *entry.key();
+        let mut ring = vec![start];
+        let mut next = entry.get_mut().pop().unwrap();
+        if entry.get().is_empty() {
+            entry.remove();
+        }
+        while next != start {
+            ring.push(next);
+            let targets = outgoing.get_mut(&next).unwrap();
+            let target = targets.pop().unwrap();
+            if targets.is_empty() {
+                outgoing.remove(&next);
+            }
+            next = target;
+        }
+        let ring = remove_collinear(ring);
+        if ring.len() >= 3 {
+            rings.push(ring);
+        }
+    }
+    rings
+}
+
+/// Removes all points of the ring that lie on a straight line between their neighbors
+fn remove_collinear(ring: Vec<Vertex>) -> Vec<Vertex> {
+    let mut result: Vec<Vertex> = Vec::with_capacity(ring.len());
+    for p in ring {
+        while result.len() >= 2 && cross(result[result.len() - 2], result[result.len() - 1], p) == 0
+        {
+            result.pop();
+        }
+        result.push(p);
+    }
+    // the ring is closed, so the same applies around its start
+    while result.len() >= 3 {
+        let n = result.len();
+        if cross(result[n - 2], result[n - 1], result[0]) == 0 {
+            result.pop();
+        } else if cross(result[n - 1], result[0], result[1]) == 0 {
+            result.remove(0);
+        } else {
+            break;
+        }
+    }
+    result
+}
+
+fn cross(a: Vertex, b: Vertex, c: Vertex) -> i64 {
+    (b.0 - a.0) * (c.1 - a.1) - (c.0 - a.0) * (b.1 - a.1)
+}
+
+/// Returns twice the signed area of the given ring, positive if it is counter-clockwise
+fn signed_area(ring: &[Vertex]) -> i64 {
+    let next = ring.iter().skip(1).chain(ring.first());
+    ring.iter()
+        .zip(next)
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rectangle;
+    use crate::CountryBoundariesBuilder;
+
+    fn assert_ring_eq(expected: &[LatLon], actual: &[LatLon]) {
+        assert_eq!(expected.len(), actual.len(), "{actual:?}");
+        // rings may start anywhere
+        let start = actual
+            .iter()
+            .position(|p| is_close(p, &expected[0]))
+            .unwrap_or_else(|| panic!("{actual:?}"));
+        for (i, p) in expected.iter().enumerate() {
+            assert!(
+                is_close(p, &actual[(start + i) % actual.len()]),
+                "{actual:?}"
+            );
+        }
+    }
+
+    fn is_close(a: &LatLon, b: &LatLon) -> bool {
+        (a.latitude() - b.latitude()).abs() < 1e-3 && (a.longitude() - b.longitude()).abs() < 1e-3
+    }
+
+    #[test]
+    fn geometry_of_unknown_region() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18).unwrap().build();
+        assert_eq!(None, boundaries.geometry("A"));
+    }
+
+    #[test]
+    fn geometry_within_one_cell() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(1.0, 1.0, 2.0, 2.0)],
+                    inner: vec![],
+                },
+            )
+            .build();
+
+        let geometry = boundaries.geometry("A").unwrap();
+        assert_eq!(1, geometry.outer.len());
+        assert!(geometry.inner.is_empty());
+        assert_ring_eq(&rectangle(1.0, 1.0, 2.0, 2.0), &geometry.outer[0]);
+    }
+
+    #[test]
+    fn geometry_dissolves_seams_between_cells() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(-15.0, -15.0, 35.0, 25.0)],
+                    inner: vec![],
+                },
+            )
+            .build();
+
+        let geometry = boundaries.geometry("A").unwrap();
+        assert_eq!(1, geometry.outer.len());
+        assert!(geometry.inner.is_empty());
+        assert_ring_eq(&rectangle(-15.0, -15.0, 35.0, 25.0), &geometry.outer[0]);
+    }
+
+    #[test]
+    fn geometry_with_hole_across_cells() {
+        let mut hole = rectangle(5.0, 5.0, 15.0, 15.0);
+        hole.reverse();
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(-15.0, -15.0, 35.0, 25.0)],
+                    inner: vec![hole.clone()],
+                },
+            )
+            .build();
+
+        let geometry = boundaries.geometry("A").unwrap();
+        assert_eq!(1, geometry.outer.len());
+        assert_eq!(1, geometry.inner.len());
+        assert_ring_eq(&rectangle(-15.0, -15.0, 35.0, 25.0), &geometry.outer[0]);
+        assert_ring_eq(&hole, &geometry.inner[0]);
+    }
+
+    #[test]
+    fn geometry_is_split_at_180th_meridian() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(0.0, 175.0, 5.0, 185.0)],
+                    inner: vec![],
+                },
+            )
+            .build();
+
+        let mut geometry = boundaries.geometry("A").unwrap();
+        assert_eq!(2, geometry.outer.len());
+        geometry
+            .outer
+            .sort_by(|a, b| a[0].longitude().total_cmp(&b[0].longitude()));
+        assert_ring_eq(&rectangle(0.0, -180.0, 5.0, -175.0), &geometry.outer[0]);
+        assert_ring_eq(&rectangle(0.0, 175.0, 5.0, 180.0), &geometry.outer[1]);
+    }
+
+    #[test]
+    fn geometry_of_several_polygons() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![
+                        rectangle(1.0, 1.0, 2.0, 2.0),
+                        rectangle(1.0, 21.0, 2.0, 22.0),
+                    ],
+                    inner: vec![],
+                },
+            )
+            .build();
+
+        let geometry = boundaries.geometry("A").unwrap();
+        assert_eq!(2, geometry.outer.len());
+    }
+
+    #[test]
+    fn dissolve_splits_edges_on_cell_borders() {
+        let m = CELL_SIZE;
+        // two pieces left and right of a cell border, one with an additional vertex on it
+        let mut edges = Vec::new();
+        add_ring(
+            &mut edges,
+            [(0, 0), (m, 0), (m, 10), (m, 20), (0, 20)],
+            true,
+        );
+        add_ring(
+            &mut edges,
+            [(m, 0), (m + 10, 0), (m + 10, 20), (m, 20)],
+            true,
+        );
+        assert_eq!(
+            vec![vec![(0, 0), (m + 10, 0), (m + 10, 20), (0, 20)]],
+            rings(dissolve(edges))
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use crate::multipolygon::Point;
+use crate::{BoundingBox, LatLon};
+use std::cmp::min;
+
+/// The raster into which the world is divided, from -180° to +180° longitude (left to right) and
+/// from +90° to -90° latitude (top to bottom).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Grid {
+    /// number of cells in horizontal direction
+    pub width: usize,
+    /// number of cells in vertical direction
+    pub height: usize,
+}
+
+impl Grid {
+    /// Returns the x and y of the cell in which the given `position` is located and the position
+    /// in the local coordinates of that cell
+    pub fn cell_and_local_point(&self, position: LatLon) -> (usize, usize, Point) {
+        let normalized_longitude = normalize(position.longitude(), -180.0, 360.0);
+        let cell_x = self.longitude_to_cell_x(normalized_longitude);
+        let cell_y = self.latitude_to_cell_y(position.latitude());
+
+        (
+            cell_x,
+            cell_y,
+            Point {
+                x: self.longitude_to_local_x(cell_x, normalized_longitude),
+                y: self.latitude_to_local_y(cell_y, position.latitude()),
+            },
+        )
+    }
+
+    fn longitude_to_cell_x(&self, longitude: f64) -> usize {
+        let raster_width = self.width as f64;
+        min(
+            self.width.saturating_sub(1),
+            (raster_width * (180.0 + longitude) / 360.0).floor() as usize,
+        )
+    }
+
+    fn latitude_to_cell_y(&self, latitude: f64) -> usize {
+        let raster_height = self.height as f64;
+        ((raster_height * (90.0 - latitude) / 180.0).ceil() as usize).saturating_sub(1)
+    }
+
+    fn longitude_to_local_x(&self, cell_x: usize, longitude: f64) -> u16 {
+        let raster_width = self.width as f64;
+        let cell_x = cell_x as f64;
+        let cell_longitude = -180.0 + 360.0 * cell_x / raster_width;
+        ((longitude - cell_longitude) * raster_width * 0xffff as f64 / 360.0) as u16
+    }
+
+    fn latitude_to_local_y(&self, cell_y: usize, latitude: f64) -> u16 {
+        let raster_height = self.height as f64;
+        let cell_y = cell_y as f64;
+        let cell_latitude = 90.0 - 180.0 * (cell_y + 1.0) / raster_height;
+        ((latitude - cell_latitude) * raster_height * 0xffff as f64 / 180.0) as u16
+    }
+
+    /// Returns the latitude and longitude of the bottom left corner of the given cell
+    pub fn cell_origin(&self, x: usize, y: usize) -> (f64, f64) {
+        let (cell_height, cell_width) = self.cell_size();
+        (
+            90.0 - cell_height * (y + 1) as f64,
+            -180.0 + cell_width * x as f64,
+        )
+    }
+
+    /// Returns the height and width of a cell in degrees
+    pub fn cell_size(&self) -> (f64, f64) {
+        (180.0 / self.height as f64, 360.0 / self.width as f64)
+    }
+
+    /// Returns the cell next to the given cell in the given direction, wrapping around the 180th
+    /// meridian. Returns `None` beyond the poles.
+    pub fn neighbor(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<(usize, usize)> {
+        let y = y.checked_add_signed(dy).filter(|&y| y < self.height)?;
+        let x = (x as isize + dx).rem_euclid(self.width as isize) as usize;
+        Some((x, y))
+    }
+
+    /// Returns the cells that are `distance` cells away from the given cell, i.e. the
+    /// `distance`th ring of cells around it. Each cell of the raster is part of exactly one such
+    /// ring, so the rings are empty once all cells have been returned.
+    pub fn ring(&self, x: usize, y: usize, distance: usize) -> Vec<(usize, usize)> {
+        let r = distance as isize;
+        // each column of the raster must only be in one ring even though longitude wraps around
+        let min_dx = -((self.width as isize - 1) / 2);
+        let max_dx = self.width as isize / 2;
+        let mut result = Vec::new();
+        for dy in -r..=r {
+            for dx in min_dx.max(-r)..=max_dx.min(r) {
+                if dx.abs() != r && dy.abs() != r {
+                    continue;
+                }
+                if let Some(cell) = self.neighbor(x, y, dx, dy) {
+                    result.push(cell);
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the x and y of all the cells that overlap with the given `bounds`
+    pub fn cells(&self, bounds: &BoundingBox) -> impl Iterator<Item = (usize, usize)> {
+        let normalized_min_longitude = normalize(bounds.min_longitude(), -180.0, 360.0);
+        let normalized_max_longitude = normalize(bounds.max_longitude(), -180.0, 360.0);
+
+        let min_x = self.longitude_to_cell_x(normalized_min_longitude);
+        let max_y = self.latitude_to_cell_y(bounds.min_latitude());
+        let max_x = self.longitude_to_cell_x(normalized_max_longitude);
+        let min_y = self.latitude_to_cell_y(bounds.max_latitude());
+
+        let steps_y = max_y - min_y;
+        // might wrap around
+        let steps_x = if min_x > max_x {
+            self.width - min_x + max_x
+        } else {
+            max_x - min_x
+        };
+
+        let raster_width = self.width;
+        let mut x_step = 0;
+        let mut y_step = 0;
+
+        std::iter::from_fn(move || {
+            let result = if x_step <= steps_x && y_step <= steps_y {
+                let x = (min_x + x_step) % raster_width;
+                let y = min_y + y_step;
+                Some((x, y))
+            } else {
+                None
+            };
+
+            if y_step < steps_y {
+                y_step += 1;
+            } else {
+                y_step = 0;
+                x_step += 1;
+            }
+
+            result
+        })
+        /*
+        // this would be more elegant and shorter, but it is still experimental
+
+        return std::iter::from_generator(|| {
+            for x_step in 0..=steps_x {
+                let x = (min_x + x_step) % raster_width;
+                for y_step in 0..=steps_y {
+                    let y = y_step + min_y;
+                    yield (x, y);
+                }
+            }
+        })
+        */
+    }
+}
+
+pub fn normalize(value: f64, start_at: f64, base: f64) -> f64 {
+    let mut value = value % base;
+    if value < start_at {
+        value += base;
+    } else if value >= start_at + base {
+        value -= base;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn neighbor_wraps_around_180th_meridian() {
+        let grid = Grid {
+            width: 4,
+            height: 2,
+        };
+        assert_eq!(Some((3, 0)), grid.neighbor(0, 0, -1, 0));
+        assert_eq!(Some((0, 1)), grid.neighbor(3, 0, 1, 1));
+        assert_eq!(None, grid.neighbor(0, 0, 0, -1));
+        assert_eq!(None, grid.neighbor(0, 1, 0, 1));
+    }
+
+    #[test]
+    fn rings_contain_every_cell_once() {
+        for (width, height) in [(1, 1), (4, 2), (5, 3), (36, 18)] {
+            let grid = Grid { width, height };
+            let mut cells = HashSet::new();
+            let mut distance = 0;
+            loop {
+                let ring = grid.ring(1 % width, height / 2, distance);
+                if ring.is_empty() {
+                    break;
+                }
+                for cell in ring {
+                    assert!(cells.insert(cell));
+                }
+                distance += 1;
+            }
+            assert_eq!(width * height, cells.len());
+        }
+    }
+
+    #[test]
+    fn cell_origin() {
+        let grid = Grid {
+            width: 4,
+            height: 2,
+        };
+        assert_eq!((0.0, -180.0), grid.cell_origin(0, 0));
+        assert_eq!((-90.0, 90.0), grid.cell_origin(3, 1));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{CountryBoundaries, LatLon};
+
+impl CountryBoundaries {
+    /// Returns the id of the region the region with the given `id` is a subdivision of, or
+    /// `None` if it is not a subdivision or there is no such region.
+    ///
+    /// The hierarchy is derived from the ids, following the naming of ISO 3166-2 codes: The
+    /// parent of a region is the region whose id is the longest part of its id that ends before
+    /// a `-`, among those in the data. E.g. the parent of `US-TX` is `US`.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// assert_eq!(Some("BA"), boundaries.parent("BA-SRP"));
+    /// assert_eq!(None, boundaries.parent("BA"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parent(&self, id: &str) -> Option<&str> {
+        self.regions.get(id)?;
+        let mut prefix = id;
+        while let Some(end) = prefix.rfind('-') {
+            prefix = &prefix[..end];
+            if let Some(parent) = self.regions.get(prefix) {
+                return Some(self.regions.id(parent));
+            }
+        }
+        None
+    }
+
+    /// Returns the ids of the regions that are direct subdivisions of the region with the given
+    /// `id`, see [`CountryBoundaries::parent`].
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// let states = boundaries.children("US");
+    /// assert!(states.contains(&"US-TX"));
+    /// assert!(boundaries.children("US-TX").is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn children(&self, id: &str) -> Vec<&str> {
+        if self.regions.get(id).is_none() {
+            return Vec::new();
+        }
+        self.ids_iter()
+            .filter(|child| child.len() > id.len() + 1 && child.starts_with(id))
+            .filter(|child| self.parent(child) == Some(id))
+            .collect()
+    }
+
+    /// Returns the id of the country in which the given `position` is located, or `None` if it is
+    /// not in any country.
+    ///
+    /// Countries are the regions whose id is on the ISO 3166-1 level, i.e. contains no `-`. So a
+    /// subdivision whose country is not in the data, like `CC-D` without `CC`, is never returned.
+    /// If the position is in several countries, the smallest one is returned. E.g. for a position
+    /// in Puerto Rico, it is `PR` rather than `US`.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// assert_eq!(Some("US"), boundaries.country_of(LatLon::new(33.0, -97.0)?));
+    /// assert_eq!(None, boundaries.country_of(LatLon::new(10.0, -30.0)?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn country_of(&self, position: LatLon) -> Option<&str> {
+        self.ids(position).into_iter().find(|id| !id.contains('-'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::{latlon, polygon, rectangle};
+    use crate::CountryBoundariesBuilder;
+
+    #[test]
+    fn parent() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("AA", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .add("AA-B", polygon(rectangle(0.0, 0.0, 5.0, 5.0)))
+            .add("AA-B-C", polygon(rectangle(0.0, 0.0, 1.0, 1.0)))
+            .add("AA-X-Y", polygon(rectangle(6.0, 6.0, 7.0, 7.0)))
+            .add("CC-D", polygon(rectangle(20.0, 20.0, 30.0, 30.0)))
+            .build();
+
+        assert_eq!(None, boundaries.parent("AA"));
+        assert_eq!(Some("AA"), boundaries.parent("AA-B"));
+        assert_eq!(Some("AA-B"), boundaries.parent("AA-B-C"));
+        // skips levels that are not in the data
+        assert_eq!(Some("AA"), boundaries.parent("AA-X-Y"));
+        assert_eq!(None, boundaries.parent("CC-D"));
+        assert_eq!(None, boundaries.parent("AA-Z"));
+    }
+
+    #[test]
+    fn children() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("AA", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .add("AA-B", polygon(rectangle(0.0, 0.0, 5.0, 5.0)))
+            .add("AA-B-C", polygon(rectangle(0.0, 0.0, 1.0, 1.0)))
+            .add("AA-D", polygon(rectangle(5.0, 5.0, 10.0, 10.0)))
+            .add("AAA", polygon(rectangle(20.0, 20.0, 30.0, 30.0)))
+            .add("AAA-E", polygon(rectangle(20.0, 20.0, 30.0, 30.0)))
+            .build();
+
+        let mut children = boundaries.children("AA");
+        children.sort();
+        assert_eq!(vec!["AA-B", "AA-D"], children);
+        assert_eq!(vec!["AA-B-C"], boundaries.children("AA-B"));
+        assert!(boundaries.children("AA-D").is_empty());
+        assert!(boundaries.children("XX").is_empty());
+    }
+
+    #[test]
+    fn country_of() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("AA", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .add("AA-B", polygon(rectangle(0.0, 0.0, 5.0, 5.0)))
+            .add("C", polygon(rectangle(8.0, 8.0, 10.0, 10.0)))
+            .add("CC-D", polygon(rectangle(20.0, 20.0, 30.0, 30.0)))
+            .build();
+
+        assert_eq!(Some("AA"), boundaries.country_of(latlon(1.0, 1.0)));
+        assert_eq!(Some("AA"), boundaries.country_of(latlon(6.0, 6.0)));
+        assert_eq!(Some("C"), boundaries.country_of(latlon(9.0, 9.0)));
+        assert_eq!(None, boundaries.country_of(latlon(25.0, 25.0)));
+        assert_eq!(None, boundaries.country_of(latlon(40.0, 40.0)));
+    }
+}
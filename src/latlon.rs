@@ -1,6 +1,6 @@
 use crate::error::Error;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct LatLon {
     latitude: f64,
     longitude: f64,
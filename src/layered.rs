@@ -0,0 +1,317 @@
+use crate::{BoundingBox, CountryBoundaries, LatLon};
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum LayerError {
+    InvalidName { name: String },
+    DuplicateName { name: String },
+}
+
+impl std::error::Error for LayerError {}
+
+impl fmt::Display for LayerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayerError::InvalidName { name } => {
+                write!(f, "layer name '{name}' is invalid, it must not contain ':'")
+            }
+            LayerError::DuplicateName { name } => {
+                write!(f, "there is already a layer with the name '{name}'")
+            }
+        }
+    }
+}
+
+/// Several [`CountryBoundaries`] queried as one, e.g. custom regions like low-emission zones or
+/// sales territories on top of the default data. The layers may have different raster sizes.
+///
+/// Each layer has a name that is used as namespace for its ids: the id `berlin` of the layer
+/// `lez` is `lez:berlin`. The ids of a layer with an empty name are not namespaced, which is
+/// useful for the country data.
+///
+/// # Example
+/// ```
+/// # use country_boundaries::{CountryBoundaries, CountryBoundariesBuilder, LatLon, Multipolygon};
+/// # use country_boundaries::{LayeredBoundaries, BOUNDARIES_ODBL_360X180};
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let countries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+/// let zone = Multipolygon {
+///     outer: vec![vec![
+///         LatLon::new(52.45, 13.28)?,
+///         LatLon::new(52.45, 13.48)?,
+///         LatLon::new(52.57, 13.48)?,
+///         LatLon::new(52.57, 13.28)?,
+///     ]],
+///     inner: vec![],
+/// };
+/// let zones = CountryBoundariesBuilder::new(360, 180)?.add("berlin", zone).build();
+///
+/// let boundaries = LayeredBoundaries::new()
+///     .add("lez", zones)?
+///     .add("", countries)?;
+///
+/// let berlin = LatLon::new(52.52, 13.40)?;
+/// let ids: Vec<String> = boundaries.ids(berlin).iter().map(|id| id.to_string()).collect();
+/// assert_eq!(vec!["lez:berlin", "DE"], ids);
+/// assert!(boundaries.is_in(berlin, "lez:berlin"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LayeredBoundaries {
+    layers: Vec<(String, CountryBoundaries)>,
+}
+
+/// An id of a region in a [`LayeredBoundaries`], displayed as `layer:id`, or only as `id` if the
+/// name of the layer is empty
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LayeredId<'a> {
+    /// name of the layer the region is in
+    pub layer: &'a str,
+    /// id of the region within its layer
+    pub id: &'a str,
+}
+
+impl fmt::Display for LayeredId<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.layer.is_empty() {
+            write!(f, "{}", self.id)
+        } else {
+            write!(f, "{}:{}", self.layer, self.id)
+        }
+    }
+}
+
+impl LayeredBoundaries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the given `boundaries` as a layer with the given `name` below the layers added so
+    /// far.
+    ///
+    /// # Errors
+    /// If the name contains a `:` or there is already a layer with that name
+    pub fn add(mut self, name: &str, boundaries: CountryBoundaries) -> Result<Self, LayerError> {
+        if name.contains(':') {
+            return Err(LayerError::InvalidName {
+                name: name.to_string(),
+            });
+        }
+        if self.layer(name).is_some() {
+            return Err(LayerError::DuplicateName {
+                name: name.to_string(),
+            });
+        }
+        self.layers.push((name.to_string(), boundaries));
+        Ok(self)
+    }
+
+    /// Returns the boundaries of the layer with the given `name`
+    pub fn layer(&self, name: &str) -> Option<&CountryBoundaries> {
+        self.layers
+            .iter()
+            .find(|(layer, _)| layer == name)
+            .map(|(_, boundaries)| boundaries)
+    }
+
+    /// Returns the names of the layers, from the top to the bottom layer
+    pub fn layer_names(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the ids of the regions the given `position` is in: the ids of the top layer
+    /// first, then those of the layers below, in the order in which the layers were added.
+    /// Within each layer, the ids are ordered by size of the region ascending, see
+    /// [`CountryBoundaries::ids`].
+    pub fn ids(&self, position: LatLon) -> Vec<LayeredId<'_>> {
+        self.layers
+            .iter()
+            .flat_map(|(layer, boundaries)| {
+                boundaries
+                    .ids(position)
+                    .into_iter()
+                    .map(move |id| LayeredId { layer, id })
+            })
+            .collect()
+    }
+
+    /// Returns whether the given `position` is in the region with the given namespaced `id`,
+    /// e.g. `lez:berlin`
+    pub fn is_in(&self, position: LatLon, id: &str) -> bool {
+        match self.resolve(id) {
+            Some((boundaries, id)) => boundaries.is_in(position, id),
+            None => false,
+        }
+    }
+
+    /// Returns whether the given `position` is in any of the regions with the given namespaced
+    /// `ids`
+    pub fn is_in_any(&self, position: LatLon, ids: &HashSet<&str>) -> bool {
+        self.layers.iter().any(|(layer, boundaries)| {
+            let layer_ids: HashSet<&str> = ids
+                .iter()
+                .filter_map(|id| match self.split(id) {
+                    (l, id) if l == layer => Some(id),
+                    _ => None,
+                })
+                .collect();
+            !layer_ids.is_empty() && boundaries.is_in_any(position, &layer_ids)
+        })
+    }
+
+    /// Returns the ids of the regions that fully contain the given bounding box `bounds`, see
+    /// [`CountryBoundaries::containing_ids`]
+    pub fn containing_ids(&self, bounds: BoundingBox) -> HashSet<LayeredId<'_>> {
+        self.layers
+            .iter()
+            .flat_map(|(layer, boundaries)| {
+                boundaries
+                    .containing_ids(bounds)
+                    .into_iter()
+                    .map(move |id| LayeredId { layer, id })
+            })
+            .collect()
+    }
+
+    /// Returns the ids of the regions that contain or at least intersect with the given bounding
+    /// box `bounds`, see [`CountryBoundaries::intersecting_ids`]
+    pub fn intersecting_ids(&self, bounds: BoundingBox) -> HashSet<LayeredId<'_>> {
+        self.layers
+            .iter()
+            .flat_map(|(layer, boundaries)| {
+                boundaries
+                    .intersecting_ids(bounds)
+                    .into_iter()
+                    .map(move |id| LayeredId { layer, id })
+            })
+            .collect()
+    }
+
+    /// Splits the given namespaced `id` into the name of the layer and the id within the layer.
+    /// Ids without the name of an existing layer belong to the layer with the empty name.
+    fn split<'a>(&self, id: &'a str) -> (&'a str, &'a str) {
+        match id.split_once(':') {
+            Some((layer, layer_id)) if self.layer(layer).is_some() => (layer, layer_id),
+            _ => ("", id),
+        }
+    }
+
+    fn resolve<'a>(&self, id: &'a str) -> Option<(&CountryBoundaries, &'a str)> {
+        let (layer, id) = self.split(id);
+        Some((self.layer(layer)?, id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{bbox, latlon, polygon, rectangle};
+    use crate::CountryBoundariesBuilder;
+
+    fn id<'a>(layer: &'a str, id: &'a str) -> LayeredId<'a> {
+        LayeredId { layer, id }
+    }
+
+    /// a layer with the countries A and B and a layer "zone" in a different raster size with
+    /// the zone A, which is within country A
+    fn boundaries() -> LayeredBoundaries {
+        let countries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .add("B", polygon(rectangle(0.0, 10.0, 10.0, 20.0)))
+            .build();
+        let zones = CountryBoundariesBuilder::new(360, 180)
+            .unwrap()
+            .add("A", polygon(rectangle(2.0, 2.0, 4.0, 4.0)))
+            .build();
+        LayeredBoundaries::new()
+            .add("zone", zones)
+            .unwrap()
+            .add("", countries)
+            .unwrap()
+    }
+
+    #[test]
+    fn ids_are_ordered_by_layer() {
+        let boundaries = boundaries();
+        assert_eq!(
+            vec![id("zone", "A"), id("", "A")],
+            boundaries.ids(latlon(3.0, 3.0))
+        );
+        assert_eq!(vec![id("", "A")], boundaries.ids(latlon(5.0, 5.0)));
+        assert_eq!(vec![id("", "B")], boundaries.ids(latlon(5.0, 15.0)));
+        assert!(boundaries.ids(latlon(-5.0, 5.0)).is_empty());
+    }
+
+    #[test]
+    fn display_namespaced_ids() {
+        assert_eq!("zone:A", id("zone", "A").to_string());
+        assert_eq!("A", id("", "A").to_string());
+    }
+
+    #[test]
+    fn is_in_namespaced_ids() {
+        let boundaries = boundaries();
+        assert!(boundaries.is_in(latlon(3.0, 3.0), "zone:A"));
+        assert!(boundaries.is_in(latlon(3.0, 3.0), "A"));
+        assert!(!boundaries.is_in(latlon(5.0, 5.0), "zone:A"));
+        assert!(boundaries.is_in(latlon(5.0, 5.0), "A"));
+        assert!(!boundaries.is_in(latlon(3.0, 3.0), "unknown:A"));
+    }
+
+    #[test]
+    fn is_in_any_namespaced_ids() {
+        let boundaries = boundaries();
+        assert!(boundaries.is_in_any(latlon(3.0, 3.0), &HashSet::from(["zone:A", "B"])));
+        assert!(boundaries.is_in_any(latlon(5.0, 15.0), &HashSet::from(["zone:A", "B"])));
+        assert!(!boundaries.is_in_any(latlon(5.0, 5.0), &HashSet::from(["zone:A", "B"])));
+        assert!(!boundaries.is_in_any(latlon(3.0, 3.0), &HashSet::new()));
+    }
+
+    #[test]
+    fn bounding_box_queries() {
+        let boundaries = boundaries();
+        assert_eq!(
+            HashSet::from([id("zone", "A"), id("", "A")]),
+            boundaries.containing_ids(bbox(2.5, 2.5, 3.5, 3.5))
+        );
+        assert_eq!(
+            HashSet::from([id("", "A")]),
+            boundaries.containing_ids(bbox(1.0, 1.0, 5.0, 5.0))
+        );
+        assert_eq!(
+            HashSet::from([id("zone", "A"), id("", "A"), id("", "B")]),
+            boundaries.intersecting_ids(bbox(3.0, 3.0, 5.0, 15.0))
+        );
+    }
+
+    #[test]
+    fn layers() {
+        let boundaries = boundaries();
+        assert_eq!(
+            vec!["zone", ""],
+            boundaries.layer_names().collect::<Vec<_>>()
+        );
+        assert!(boundaries.layer("zone").is_some());
+        assert!(boundaries.layer("unknown").is_none());
+    }
+
+    #[test]
+    fn invalid_layer_names() {
+        let empty = CountryBoundariesBuilder::new(1, 1).unwrap().build();
+        assert!(matches!(
+            LayeredBoundaries::new().add("a:b", empty.clone()),
+            Err(LayerError::InvalidName { .. })
+        ));
+        assert!(matches!(
+            LayeredBoundaries::new()
+                .add("a", empty.clone())
+                .unwrap()
+                .add("a", empty),
+            Err(LayerError::DuplicateName { .. })
+        ));
+    }
+}
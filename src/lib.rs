@@ -2,28 +2,72 @@
 #![doc = include_str!("../README.md")]
 
 use crate::deserializer::from_reader;
+use crate::serializer::to_writer;
 use cell::Cell;
+use grid::Grid;
 use multipolygon::Point;
-use std::{cmp::min, collections::HashMap, collections::HashSet, io, vec::Vec};
+use region::Regions;
+use std::{collections::HashSet, io, vec::Vec};
 
 pub use self::bbox::BoundingBox;
+pub use self::boundaries_ref::CountryBoundariesRef;
 pub use self::deserializer::ReadError;
 pub use self::error::Error;
+pub use self::generator::{BuildError, CountryBoundariesBuilder};
+#[cfg(feature = "geojson")]
+pub use self::geojson::GeoJsonError;
 pub use self::latlon::LatLon;
-
+pub use self::layered::{LayerError, LayeredBoundaries, LayeredId};
+pub use self::multipolygon::Multipolygon;
+#[cfg(feature = "osm")]
+pub use self::osm::OsmXmlError;
+pub use self::region::{RegionId, RegionIdSet};
+pub use self::route::{Crossing, CrossingKind};
+pub use self::serializer::WriteError;
+#[cfg(feature = "shared")]
+pub use self::shared::{FileWatcher, SharedBoundaries};
+pub use self::statistics::Statistics;
+
+mod batch;
 mod bbox;
+mod boundaries_ref;
 mod cell;
+mod clip;
+mod coverage;
 mod deserializer;
 mod error;
+mod exact;
+mod extent;
+mod generator;
+#[cfg(feature = "geo")]
+mod geo;
+#[cfg(feature = "geojson")]
+mod geojson;
+mod geometry;
+mod grid;
+mod hierarchy;
 mod latlon;
+mod layered;
 mod multipolygon;
+mod nearest;
+#[cfg(feature = "osm")]
+mod osm;
+mod polygon;
+mod region;
+mod route;
+#[cfg(feature = "serde")]
+mod serde_support;
+mod serializer;
+#[cfg(feature = "shared")]
+mod shared;
+mod statistics;
 
 /// Bytes of the ODbL licensed data in a 360x180 raster, (c) OpenStreetMap contributors.
-pub static BOUNDARIES_ODBL_360X180: &'static [u8] = include_bytes!("../data/boundaries360x180.ser");
+pub static BOUNDARIES_ODBL_360X180: &[u8] = include_bytes!("../data/boundaries360x180.ser");
 /// Bytes of the ODbL licensed data in a 180x90 raster, (c) OpenStreetMap contributors.
-pub static BOUNDARIES_ODBL_180X90: &'static [u8] = include_bytes!("../data/boundaries180x90.ser");
+pub static BOUNDARIES_ODBL_180X90: &[u8] = include_bytes!("../data/boundaries180x90.ser");
 /// Bytes of the ODbL licensed data in a 60x30 raster, (c) OpenStreetMap contributors.
-pub static BOUNDARIES_ODBL_60X30: &'static [u8] = include_bytes!("../data/boundaries60x30.ser");
+pub static BOUNDARIES_ODBL_60X30: &[u8] = include_bytes!("../data/boundaries60x30.ser");
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CountryBoundaries {
@@ -31,8 +75,8 @@ pub struct CountryBoundaries {
     raster: Vec<Cell>,
     /// width of the raster
     raster_width: usize,
-    /// the sizes of the different countries contained
-    geometry_sizes: HashMap<String, f64>,
+    /// the ids and sizes of the different countries contained
+    regions: Regions,
 }
 
 impl CountryBoundaries {
@@ -44,6 +88,29 @@ impl CountryBoundaries {
         from_reader(reader)
     }
 
+    /// Write this `CountryBoundaries` to a stream of bytes, in the same format that is read by
+    /// [`CountryBoundaries::from_reader`].
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_60X30};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_60X30)?;
+    /// let mut bytes = Vec::new();
+    /// boundaries.write_to(&mut bytes)?;
+    /// assert_eq!(BOUNDARIES_ODBL_60X30, bytes.as_slice());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if writing to the stream failed or if the data does not fit into the
+    /// file format, e.g. because an id is longer than 65535 bytes.
+    pub fn write_to(&self, writer: impl io::Write) -> Result<(), WriteError> {
+        to_writer(writer, self)
+    }
+
     /// Returns whether the given `position` is in the region with the given `id`
     ///
     /// # Example
@@ -57,8 +124,28 @@ impl CountryBoundaries {
     /// # }
     /// ```
     pub fn is_in(&self, position: LatLon, id: &str) -> bool {
+        self.region_id(id)
+            .is_some_and(|region| self.is_in_region(position, region))
+    }
+
+    /// Returns whether the given `position` is in the region with the given handle `region`.
+    ///
+    /// Like [`CountryBoundaries::is_in`], but no id needs to be looked up.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// let germany = boundaries.region_id("DE").unwrap();
+    /// assert!(boundaries.is_in_region(LatLon::new(47.6973, 8.6910)?, germany));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_in_region(&self, position: LatLon, region: RegionId) -> bool {
         let (cell, point) = self.cell_and_local_point(position);
-        cell.is_in(point, id)
+        cell.is_in(point, region)
     }
 
     /// Returns whether the given `position` is in any of the regions with the given `ids`.
@@ -83,7 +170,16 @@ impl CountryBoundaries {
     /// ```
     pub fn is_in_any(&self, position: LatLon, ids: &HashSet<&str>) -> bool {
         let (cell, point) = self.cell_and_local_point(position);
-        cell.is_in_any(point, ids)
+        cell.is_in_any(point, |region| ids.contains(self.regions.id(region)))
+    }
+
+    /// Returns whether the given `position` is in any of the given `regions`.
+    ///
+    /// Like [`CountryBoundaries::is_in_any`], but checking whether a region is in the set is
+    /// just a bit test. See [`RegionIdSet`] for an example.
+    pub fn is_in_any_region(&self, position: LatLon, regions: &RegionIdSet) -> bool {
+        let (cell, point) = self.cell_and_local_point(position);
+        cell.is_in_any(point, |region| regions.contains(region))
     }
 
     /// Returns the ids of the regions the given `position` is contained in, ordered by size of
@@ -104,15 +200,101 @@ impl CountryBoundaries {
     /// # }
     /// ```
     pub fn ids(&self, position: LatLon) -> Vec<&str> {
+        self.region_ids(position)
+            .into_iter()
+            .map(|region| self.regions.id(region))
+            .collect()
+    }
+
+    /// Returns the handles of the regions the given `position` is contained in, ordered by size
+    /// of the region ascending.
+    ///
+    /// Like [`CountryBoundaries::ids`], but returns handles instead of ids.
+    pub fn region_ids(&self, position: LatLon) -> Vec<RegionId> {
         let (cell, point) = self.cell_and_local_point(position);
-        let mut result = cell.get_ids(point);
-        let zero = 0.0;
-        result.sort_by(|&a, &b| {
-            let a = self.geometry_sizes.get(a).unwrap_or(&zero);
-            let b = self.geometry_sizes.get(b).unwrap_or(&zero);
-            a.total_cmp(b)
-        });
-        result
+        self.region_ids_in_cell(cell, point)
+    }
+
+    /// Returns the handle of the region with the given `id`, or `None` if there is no such
+    /// region in the data.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// let germany = boundaries.region_id("DE").unwrap();
+    /// assert_eq!(Some("DE"), boundaries.id_of(germany));
+    /// assert_eq!(None, boundaries.region_id("XX"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn region_id(&self, id: &str) -> Option<RegionId> {
+        self.regions.get(id)
+    }
+
+    /// Returns the id of the region with the given handle `region`, or `None` if the handle
+    /// does not belong to this `CountryBoundaries`.
+    pub fn id_of(&self, region: RegionId) -> Option<&str> {
+        (region.index() < self.regions.len()).then(|| self.regions.id(region))
+    }
+
+    /// Returns an iterator over the ids of all regions in the data.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// let us_states = boundaries.ids_iter().filter(|id| id.starts_with("US-")).count();
+    /// assert!(us_states >= 50);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ids_iter(&self) -> impl Iterator<Item = &str> {
+        self.regions.ids()
+    }
+
+    /// Returns the ids of all regions in the data.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// let ids = boundaries.all_ids();
+    /// assert!(ids.contains("DE"));
+    /// assert!(ids.contains("US-TX"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn all_ids(&self) -> HashSet<&str> {
+        self.ids_iter().collect()
+    }
+
+    /// Returns the size of the region with the given `id`, or `None` if there is no such region
+    /// in the data.
+    ///
+    /// The results of [`CountryBoundaries::ids`] are sorted by this size. It is usually the area
+    /// of the region, but datasets may define it differently. Regions for which the data does
+    /// not specify a size have a size of 0.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// assert!(boundaries.size_of("US-TX").unwrap() < boundaries.size_of("US").unwrap());
+    /// assert_eq!(None, boundaries.size_of("XX"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn size_of(&self, id: &str) -> Option<f64> {
+        self.regions.get(id).map(|region| self.regions.size(region))
     }
 
     /// Returns the ids of the regions that fully contain the given bounding box `bounds`.
@@ -135,24 +317,20 @@ impl CountryBoundaries {
     /// # }
     /// ```
     pub fn containing_ids(&self, bounds: BoundingBox) -> HashSet<&str> {
-        let mut ids: HashSet<&str> = HashSet::new();
+        let mut ids: Vec<RegionId> = Vec::new();
         let mut first_cell = true;
         for cell in self.cells(&bounds) {
             if first_cell {
-                ids.extend(cell.containing_ids.iter().map(String::as_str));
+                ids.extend(&cell.containing_ids);
                 first_cell = false;
             } else {
-                ids.retain(|&id| {
-                    cell.containing_ids
-                        .iter()
-                        .any(|containing_id| containing_id == id)
-                });
+                ids.retain(|id| cell.containing_ids.contains(id));
                 if ids.is_empty() {
-                    return ids;
+                    break;
                 }
             }
         }
-        ids
+        ids.into_iter().map(|id| self.regions.id(id)).collect()
     }
 
     /// Returns the ids of the regions that contain or at lest intersect with the given bounding box
@@ -178,153 +356,89 @@ impl CountryBoundaries {
     pub fn intersecting_ids(&self, bounds: BoundingBox) -> HashSet<&str> {
         let mut ids: HashSet<&str> = HashSet::new();
         for cell in self.cells(&bounds) {
-            ids.extend(cell.get_all_ids());
+            ids.extend(cell.get_all_ids().map(|id| self.regions.id(id)));
         }
         ids
     }
 
+    fn region_ids_in_cell(&self, cell: &Cell, point: Point) -> Vec<RegionId> {
+        let mut result = cell.get_ids(point);
+        result.sort_by(|&a, &b| self.regions.size(a).total_cmp(&self.regions.size(b)));
+        result
+    }
+
     fn cell_and_local_point(&self, position: LatLon) -> (&Cell, Point) {
-        let normalized_longitude = normalize(position.longitude(), -180.0, 360.0);
-        let cell_x = self.longitude_to_cell_x(normalized_longitude);
-        let cell_y = self.latitude_to_cell_y(position.latitude());
-
-        (
-            self.cell(cell_x, cell_y),
-            Point {
-                x: self.longitude_to_local_x(cell_x, normalized_longitude),
-                y: self.latitude_to_local_y(cell_y, position.latitude()),
-            },
-        )
+        let (x, y, point) = self.grid().cell_and_local_point(position);
+        (self.cell(x, y), point)
     }
 
     fn cell(&self, x: usize, y: usize) -> &Cell {
         &self.raster[y * self.raster_width + x]
     }
 
-    fn longitude_to_cell_x(&self, longitude: f64) -> usize {
-        let raster_width = self.raster_width as f64;
-        min(
-            self.raster_width.saturating_sub(1),
-            (raster_width * (180.0 + longitude) / 360.0).floor() as usize,
-        )
+    fn cells(&self, bounds: &BoundingBox) -> impl Iterator<Item = &Cell> {
+        self.grid().cells(bounds).map(|(x, y)| self.cell(x, y))
     }
 
-    fn latitude_to_cell_y(&self, latitude: f64) -> usize {
-        let raster_height = (self.raster.len() / self.raster_width) as f64;
-        ((raster_height * (90.0 - latitude) / 180.0).ceil() as usize).saturating_sub(1)
+    fn grid(&self) -> Grid {
+        Grid {
+            width: self.raster_width,
+            height: self.raster.len() / self.raster_width,
+        }
     }
+}
 
-    fn longitude_to_local_x(&self, cell_x: usize, longitude: f64) -> u16 {
-        let raster_width = self.raster_width as f64;
-        let cell_x = cell_x as f64;
-        let cell_longitude = -180.0 + 360.0 * cell_x / raster_width;
-        ((longitude - cell_longitude) * raster_width * 0xffff as f64 / 360.0) as u16
-    }
+#[cfg(test)]
+pub(crate) mod test_util {
+    use crate::{BoundingBox, LatLon, Multipolygon};
 
-    fn latitude_to_local_y(&self, cell_y: usize, latitude: f64) -> u16 {
-        let raster_height = (self.raster.len() / self.raster_width) as f64;
-        let cell_y = cell_y as f64;
-        let cell_latitude = 90.0 - 180.0 * (cell_y + 1.0) / raster_height;
-        ((latitude - cell_latitude) * raster_height * 0xffff as f64 / 180.0) as u16
+    pub fn latlon(latitude: f64, longitude: f64) -> LatLon {
+        LatLon::new(latitude, longitude).unwrap()
     }
 
-    fn cells(&self, bounds: &BoundingBox) -> impl Iterator<Item = &Cell> {
-        let normalized_min_longitude = normalize(bounds.min_longitude(), -180.0, 360.0);
-        let normalized_max_longitude = normalize(bounds.max_longitude(), -180.0, 360.0);
-
-        let min_x = self.longitude_to_cell_x(normalized_min_longitude);
-        let max_y = self.latitude_to_cell_y(bounds.min_latitude());
-        let max_x = self.longitude_to_cell_x(normalized_max_longitude);
-        let min_y = self.latitude_to_cell_y(bounds.max_latitude());
-
-        let steps_y = max_y - min_y;
-        // might wrap around
-        let steps_x = if min_x > max_x {
-            self.raster_width - min_x + max_x
-        } else {
-            max_x - min_x
-        };
-
-        let mut x_step = 0;
-        let mut y_step = 0;
-
-        std::iter::from_fn(move || {
-            let result = if x_step <= steps_x && y_step <= steps_y {
-                let x = (min_x + x_step) % self.raster_width;
-                let y = min_y + y_step;
-                Some(self.cell(x, y))
-            } else {
-                None
-            };
-
-            if y_step < steps_y {
-                y_step += 1;
-            } else {
-                y_step = 0;
-                x_step += 1;
-            }
+    /// the ring of a rectangle from the given minimum to the given maximum position
+    pub fn rectangle(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> Vec<LatLon> {
+        vec![
+            latlon(min_lat, min_lon),
+            latlon(min_lat, max_lon),
+            latlon(max_lat, max_lon),
+            latlon(max_lat, min_lon),
+        ]
+    }
 
-            result
-        })
-        /*
-        // this would be more elegant and shorter, but it is still experimental
-
-        return std::iter::from_generator(|| {
-            for x_step in 0..=steps_x {
-                let x = (min_x + x_step) % self.raster_width;
-                for y_step in 0..=steps_y {
-                    let y = y_step + min_y;
-                    yield &self.raster[y * self.raster_width + x];
-                }
-            }
-        })
-        */
+    /// a polygon without holes with the given outer `ring`
+    pub fn polygon(ring: Vec<LatLon>) -> Multipolygon<LatLon> {
+        Multipolygon {
+            outer: vec![ring],
+            inner: vec![],
+        }
     }
-}
 
-fn normalize(value: f64, start_at: f64, base: f64) -> f64 {
-    let mut value = value % base;
-    if value < start_at {
-        value += base;
-    } else if value >= start_at + base {
-        value -= base;
+    pub fn bbox(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> BoundingBox {
+        BoundingBox::new(min_lat, min_lon, max_lat, max_lon).unwrap()
     }
-    value
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::LatLon;
-
     use super::*;
-
-    // just a convenience macro that constructs a cell
-    macro_rules! cell {
-        ($containing_ids: expr) => {
-            Cell {
-                containing_ids: $containing_ids.iter().map(|&s| String::from(s)).collect(),
+    use crate::test_util::{bbox, latlon};
+
+    // just a convenience function that constructs a raster of cells with the given containing ids
+    fn boundaries(raster: &[&[&str]], raster_width: usize) -> CountryBoundaries {
+        let mut regions = Regions::default();
+        let raster = raster
+            .iter()
+            .map(|ids| Cell {
+                containing_ids: ids.iter().map(|id| regions.insert(id)).collect(),
                 intersecting_areas: vec![],
-            }
-        };
-        ($containing_ids: expr, $intersecting_areas: expr) => {
-            Cell {
-                containing_ids: $containing_ids.iter().map(|&s| String::from(s)).collect(),
-                intersecting_areas: $intersecting_areas,
-            }
-        };
-    }
-
-    fn latlon(latitude: f64, longitude: f64) -> LatLon {
-        LatLon::new(latitude, longitude).unwrap()
-    }
-
-    fn bbox(
-        min_latitude: f64,
-        min_longitude: f64,
-        max_latitude: f64,
-        max_longitude: f64,
-    ) -> BoundingBox {
-        BoundingBox::new(min_latitude, min_longitude, max_latitude, max_longitude).unwrap()
+            })
+            .collect();
+        CountryBoundaries {
+            raster,
+            raster_width,
+            regions,
+        }
     }
 
     #[test]
@@ -335,11 +449,7 @@ mod tests {
         // ├─┼─┤
         // │C│D│
         // └─┴─┘
-        let boundaries = CountryBoundaries {
-            raster: vec![cell!(&["A"]), cell!(&["B"]), cell!(&["C"]), cell!(&["D"])],
-            raster_width: 2,
-            geometry_sizes: HashMap::new(),
-        };
+        let boundaries = boundaries(&[&["A"], &["B"], &["C"], &["D"]], 2);
 
         assert_eq!(vec!["C"], boundaries.ids(latlon(-90.0, -180.0)));
         assert_eq!(vec!["C"], boundaries.ids(latlon(-90.0, -90.0)));
@@ -366,11 +476,7 @@ mod tests {
 
     #[test]
     fn no_array_index_out_of_bounds_at_world_edges() {
-        let boundaries = CountryBoundaries {
-            raster: vec![cell!(&["A"])],
-            raster_width: 1,
-            geometry_sizes: HashMap::new(),
-        };
+        let boundaries = boundaries(&[&["A"]], 1);
 
         boundaries.ids(latlon(-90.0, -180.0));
         boundaries.ids(latlon(90.0, 180.0));
@@ -380,31 +486,37 @@ mod tests {
 
     #[test]
     fn get_containing_ids_sorted_by_size_ascending() {
-        let boundaries = CountryBoundaries {
-            raster: vec![cell!(&["D", "B", "C", "A"])],
-            raster_width: 1,
-            geometry_sizes: HashMap::from([
-                (String::from("A"), 10.0),
-                (String::from("B"), 15.0),
-                (String::from("C"), 100.0),
-                (String::from("D"), 800.0),
-            ]),
-        };
+        let mut boundaries = boundaries(&[&["D", "B", "C", "A"]], 1);
+        for (id, size) in [("A", 10.0), ("B", 15.0), ("C", 100.0), ("D", 800.0)] {
+            let region = boundaries.regions.get(id).unwrap();
+            boundaries.regions.set_size(region, size);
+        }
         assert_eq!(vec!["A", "B", "C", "D"], boundaries.ids(latlon(1.0, 1.0)));
     }
 
+    #[test]
+    fn all_ids() {
+        let boundaries = boundaries(&[&["A"], &["B", "A"], &[], &["C"]], 2);
+        assert_eq!(
+            vec!["A", "B", "C"],
+            boundaries.ids_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(HashSet::from(["A", "B", "C"]), boundaries.all_ids());
+    }
+
+    #[test]
+    fn size_of() {
+        let mut boundaries = boundaries(&[&["A", "B"]], 1);
+        let region = boundaries.regions.get("A").unwrap();
+        boundaries.regions.set_size(region, 10.0);
+        assert_eq!(Some(10.0), boundaries.size_of("A"));
+        assert_eq!(Some(0.0), boundaries.size_of("B"));
+        assert_eq!(None, boundaries.size_of("C"));
+    }
+
     #[test]
     fn get_intersecting_ids_in_bbox_is_merged_correctly() {
-        let boundaries = CountryBoundaries {
-            raster: vec![
-                cell!(&["A"]),
-                cell!(&["B"]),
-                cell!(&["C"]),
-                cell!(&["D", "E"]),
-            ],
-            raster_width: 2,
-            geometry_sizes: HashMap::new(),
-        };
+        let boundaries = boundaries(&[&["A"], &["B"], &["C"], &["D", "E"]], 2);
         assert_eq!(
             HashSet::from(["A", "B", "C", "D", "E"]),
             boundaries.intersecting_ids(bbox(-10.0, -10.0, 10.0, 10.0))
@@ -413,11 +525,7 @@ mod tests {
 
     #[test]
     fn get_intersecting_ids_in_bbox_wraps_longitude_correctly() {
-        let boundaries = CountryBoundaries {
-            raster: vec![cell!(&["A"]), cell!(&["B"]), cell!(&["C"])],
-            raster_width: 3,
-            geometry_sizes: HashMap::new(),
-        };
+        let boundaries = boundaries(&[&["A"], &["B"], &["C"]], 3);
         assert_eq!(
             HashSet::from(["A", "C"]),
             boundaries.intersecting_ids(bbox(0.0, 170.0, 1.0, -170.0))
@@ -426,11 +534,7 @@ mod tests {
 
     #[test]
     fn get_containing_ids_in_bbox_wraps_longitude_correctly() {
-        let boundaries = CountryBoundaries {
-            raster: vec![cell!(&["A", "B", "C"]), cell!(&["X"]), cell!(&["A", "B"])],
-            raster_width: 3,
-            geometry_sizes: HashMap::new(),
-        };
+        let boundaries = boundaries(&[&["A", "B", "C"], &["X"], &["A", "B"]], 3);
         assert_eq!(
             HashSet::from(["A", "B"]),
             boundaries.containing_ids(bbox(0.0, 170.0, 1.0, -170.0))
@@ -439,16 +543,7 @@ mod tests {
 
     #[test]
     fn get_containing_ids_in_bbox_returns_correct_result_when_one_cell_is_empty() {
-        let boundaries = CountryBoundaries {
-            raster: vec![
-                cell!(&[] as &[&str; 0]),
-                cell!(&["A"]),
-                cell!(&["A"]),
-                cell!(&["A"]),
-            ],
-            raster_width: 2,
-            geometry_sizes: HashMap::new(),
-        };
+        let boundaries = boundaries(&[&[], &["A"], &["A"], &["A"]], 2);
         assert!(boundaries
             .containing_ids(bbox(-10.0, -10.0, 10.0, 10.0))
             .is_empty())
@@ -456,16 +551,10 @@ mod tests {
 
     #[test]
     fn get_containing_ids_in_bbox_is_merged_correctly() {
-        let boundaries = CountryBoundaries {
-            raster: vec![
-                cell!(&["A", "B"]),
-                cell!(&["B", "A"]),
-                cell!(&["C", "B", "A"]),
-                cell!(&["D", "A"]),
-            ],
-            raster_width: 2,
-            geometry_sizes: HashMap::new(),
-        };
+        let boundaries = boundaries(
+            &[&["A", "B"], &["B", "A"], &["C", "B", "A"], &["D", "A"]],
+            2,
+        );
         assert_eq!(
             HashSet::from(["A"]),
             boundaries.containing_ids(bbox(-10.0, -10.0, 10.0, 10.0))
@@ -474,11 +563,7 @@ mod tests {
 
     #[test]
     fn get_containing_ids_in_bbox_is_merged_correctly_an_nothing_is_left() {
-        let boundaries = CountryBoundaries {
-            raster: vec![cell!(&["A"]), cell!(&["B"]), cell!(&["C"]), cell!(&["D"])],
-            raster_width: 2,
-            geometry_sizes: HashMap::new(),
-        };
+        let boundaries = boundaries(&[&["A"], &["B"], &["C"], &["D"]], 2);
 
         assert!(boundaries
             .containing_ids(bbox(-10.0, -10.0, 10.0, 10.0))
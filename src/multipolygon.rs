@@ -4,14 +4,17 @@ pub struct Point {
     pub y: u16,
 }
 
+/// A multipolygon consisting of any number of outer rings and inner rings (holes).
+///
+/// Rings do not need to be closed, i.e. the last point is implicitly connected to the first point.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Multipolygon {
-    pub outer: Vec<Vec<Point>>,
-    pub inner: Vec<Vec<Point>>,
+pub struct Multipolygon<P> {
+    pub outer: Vec<Vec<P>>,
+    pub inner: Vec<Vec<P>>,
 }
 
-impl Multipolygon {
-    pub fn covers(&self, point: Point) -> bool {
+impl Multipolygon<Point> {
+    pub(crate) fn covers(&self, point: Point) -> bool {
         let mut insides = 0;
         for area in &self.outer {
             if is_point_in_polygon(point, area.as_slice()) {
@@ -37,17 +40,23 @@ impl Multipolygon {
 // http://geomalgorithms.com/a03-_inclusion.html
 
 fn is_point_in_polygon(p: Point, v: &[Point]) -> bool {
+    is_point_in_ring(p, v.iter().copied())
+}
+
+/// Returns whether the point `p` is within the ring with the points `v`. Same as
+/// `is_point_in_polygon`, only that the points of the ring can come from any source.
+pub fn is_point_in_ring(p: Point, v: impl DoubleEndedIterator<Item = Point> + Clone) -> bool {
     let mut wn = 0;
-    let mut a = match v.last() {
+    let mut a = match v.clone().next_back() {
         Some(a) => a,
         None => return false,
     };
     for b in v {
         if a.y <= p.y {
-            if b.y > p.y && is_left(*a, *b, p) > 0 {
+            if b.y > p.y && is_left(a, b, p) > 0 {
                 wn += 1;
             }
-        } else if b.y <= p.y && is_left(*a, *b, p) < 0 {
+        } else if b.y <= p.y && is_left(a, b, p) < 0 {
             wn -= 1;
         }
         a = b;
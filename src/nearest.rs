@@ -0,0 +1,413 @@
+use crate::grid::normalize;
+use crate::multipolygon::Point;
+use crate::region::RegionId;
+use crate::{CountryBoundaries, LatLon};
+use std::collections::HashMap;
+
+/// mean earth radius in meters
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+impl CountryBoundaries {
+    /// Returns the ids of the regions that are at most `max_distance` meters away from the given
+    /// `position`, together with their distance in meters. Regions the `position` is contained
+    /// in have a distance of 0. The result is ordered by distance ascending, and regions at the
+    /// same distance by size of the region ascending.
+    ///
+    /// Useful for positions just outside of the (simplified) coastline, for which `ids` returns
+    /// nothing. Distances are approximated in a projection around `position`, so they become
+    /// less accurate the farther away a region is.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // at sea, in front of the coast of Texas
+    /// let position = LatLon::new(28.0, -95.0)?;
+    /// assert!(boundaries.ids(position).is_empty());
+    ///
+    /// let nearest = boundaries.nearest_ids(position, 50_000.0);
+    /// assert_eq!(vec!["US-TX", "US"], nearest.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+    /// assert!(nearest[0].1 > 0.0 && nearest[0].1 < 50_000.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn nearest_ids(&self, position: LatLon, max_distance: f64) -> Vec<(&str, f64)> {
+        let mut distances: HashMap<RegionId, f64> = self
+            .region_ids(position)
+            .into_iter()
+            .map(|region| (region, 0.0))
+            .collect();
+
+        let projection = Projection::new(position);
+        let (x, y, _) = self.grid().cell_and_local_point(position);
+        for distance in 0.. {
+            let ring = self.grid().ring(x, y, distance);
+            if ring.is_empty() {
+                break;
+            }
+            let mut any_in_reach = false;
+            for (x, y) in ring {
+                if self.cell_distance(&projection, x, y) > max_distance {
+                    continue;
+                }
+                any_in_reach = true;
+                for region in self.cell(x, y).get_all_ids() {
+                    for (a, b) in self.border_edges(x, y, region) {
+                        let d = projection.distance_to_segment(a, b);
+                        if d <= max_distance {
+                            let entry = distances.entry(region).or_insert(d);
+                            *entry = entry.min(d);
+                        }
+                    }
+                }
+            }
+            if !any_in_reach {
+                break;
+            }
+        }
+
+        let mut result: Vec<(RegionId, f64)> = distances.into_iter().collect();
+        result.sort_by(|(a, a_distance), (b, b_distance)| {
+            a_distance
+                .total_cmp(b_distance)
+                .then(self.regions.size(*a).total_cmp(&self.regions.size(*b)))
+        });
+        result
+            .into_iter()
+            .map(|(region, d)| (self.regions.id(region), d))
+            .collect()
+    }
+
+    /// Returns the distance in meters from the given `position` to the nearest border of the
+    /// region with the given `id`, regardless of whether `position` is inside or outside of the
+    /// region. Returns `None` if there is no such region or it has no border, e.g. because it
+    /// covers the whole world.
+    ///
+    /// Like for [`CountryBoundaries::nearest_ids`], the distance is approximated.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // Schaffhausen is close to the border to Germany
+    /// let distance = boundaries.distance_to_border(LatLon::new(47.6973, 8.6340)?, "DE").unwrap();
+    /// assert!(distance < 10_000.0);
+    /// assert_eq!(None, boundaries.distance_to_border(LatLon::new(47.6973, 8.6340)?, "XX"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn distance_to_border(&self, position: LatLon, id: &str) -> Option<f64> {
+        let region = self.region_id(id)?;
+        let projection = Projection::new(position);
+        let (x, y, _) = self.grid().cell_and_local_point(position);
+        let mut nearest: Option<f64> = None;
+        for distance in 0.. {
+            let ring = self.grid().ring(x, y, distance);
+            if ring.is_empty() {
+                break;
+            }
+            let mut any_in_reach = false;
+            for (x, y) in ring {
+                if nearest.is_some_and(|n| self.cell_distance(&projection, x, y) > n) {
+                    continue;
+                }
+                any_in_reach = true;
+                for (a, b) in self.border_edges(x, y, region) {
+                    let d = projection.distance_to_segment(a, b);
+                    nearest = Some(nearest.map_or(d, |n| n.min(d)));
+                }
+            }
+            if !any_in_reach {
+                break;
+            }
+        }
+        nearest
+    }
+
+    /// Returns the edges of the given `region` in the given cell that are actual borders, as
+    /// latitude and longitude pairs. Edges along the edge of the cell where the region continues
+    /// in the neighboring cell are just seams from slicing the geometry into the raster.
+    fn border_edges(&self, x: usize, y: usize, region: RegionId) -> Vec<((f64, f64), (f64, f64))> {
+        let cell = self.cell(x, y);
+        let whole_cell = [
+            Point { x: 0, y: 0 },
+            Point { x: 0xffff, y: 0 },
+            Point {
+                x: 0xffff,
+                y: 0xffff,
+            },
+            Point { x: 0, y: 0xffff },
+        ];
+        let rings: Vec<&[Point]> = if cell.containing_ids.contains(&region) {
+            vec![&whole_cell]
+        } else {
+            cell.intersecting_areas
+                .iter()
+                .filter(|(id, _)| *id == region)
+                .flat_map(|(_, area)| area.outer.iter().chain(area.inner.iter()))
+                .map(Vec::as_slice)
+                .collect()
+        };
+
+        let (min_lat, min_lon) = self.grid().cell_origin(x, y);
+        let (height, width) = self.grid().cell_size();
+        let to_lat_lon = |p: Point| {
+            (
+                min_lat + height * p.y as f64 / 0xffff as f64,
+                min_lon + width * p.x as f64 / 0xffff as f64,
+            )
+        };
+
+        let mut result = Vec::new();
+        for ring in rings {
+            let Some(&last) = ring.last() else {
+                continue;
+            };
+            let mut a = last;
+            for &b in ring {
+                if !self.is_seam(x, y, region, a, b) {
+                    result.push((to_lat_lon(a), to_lat_lon(b)));
+                }
+                a = b;
+            }
+        }
+        result
+    }
+
+    /// Returns whether the edge from `a` to `b` of the given region in the given cell lies on
+    /// the edge of the cell and the region continues on the other side of it
+    fn is_seam(&self, x: usize, y: usize, region: RegionId, a: Point, b: Point) -> bool {
+        // the point in the middle of the edge, moved just into the neighboring cell
+        let mid = |a: u16, b: u16| ((a as u32 + b as u32) / 2) as u16;
+        let (dx, dy, point) = match (a, b) {
+            _ if a.x == 0 && b.x == 0 => (
+                -1,
+                0,
+                Point {
+                    x: 0xfffe,
+                    y: mid(a.y, b.y),
+                },
+            ),
+            _ if a.x == 0xffff && b.x == 0xffff => (
+                1,
+                0,
+                Point {
+                    x: 1,
+                    y: mid(a.y, b.y),
+                },
+            ),
+            _ if a.y == 0 && b.y == 0 => (
+                0,
+                1,
+                Point {
+                    x: mid(a.x, b.x),
+                    y: 0xfffe,
+                },
+            ),
+            _ if a.y == 0xffff && b.y == 0xffff => (
+                0,
+                -1,
+                Point {
+                    x: mid(a.x, b.x),
+                    y: 1,
+                },
+            ),
+            _ => return false,
+        };
+        match self.grid().neighbor(x, y, dx, dy) {
+            Some((x, y)) => self.cell(x, y).is_in(point, region),
+            // beyond the poles, the edge of the cell is just a point
+            None => true,
+        }
+    }
+
+    /// Returns the minimum distance in meters from the projection's origin to the given cell
+    fn cell_distance(&self, projection: &Projection, x: usize, y: usize) -> f64 {
+        let (min_lat, min_lon) = self.grid().cell_origin(x, y);
+        let (height, width) = self.grid().cell_size();
+        let min_dlon = normalize(min_lon - projection.longitude, -180.0, 360.0);
+        let lat = projection.latitude.clamp(min_lat, min_lat + height);
+        let dlon = 0.0_f64.clamp(min_dlon, min_dlon + width);
+        projection.distance_to((lat, projection.longitude + dlon))
+    }
+}
+
+/// Equirectangular projection centered on a position, in meters
+struct Projection {
+    latitude: f64,
+    longitude: f64,
+    cos_latitude: f64,
+}
+
+impl Projection {
+    fn new(position: LatLon) -> Self {
+        Self {
+            latitude: position.latitude(),
+            longitude: position.longitude(),
+            cos_latitude: position.latitude().to_radians().cos(),
+        }
+    }
+
+    fn project(&self, (latitude, longitude): (f64, f64)) -> (f64, f64) {
+        let dlon = normalize(longitude - self.longitude, -180.0, 360.0);
+        let meters_per_degree = EARTH_RADIUS.to_radians();
+        (
+            dlon * self.cos_latitude * meters_per_degree,
+            (latitude - self.latitude) * meters_per_degree,
+        )
+    }
+
+    fn distance_to(&self, position: (f64, f64)) -> f64 {
+        let (x, y) = self.project(position);
+        x.hypot(y)
+    }
+
+    /// Returns the distance from the projection's origin to the segment from `a` to `b`
+    fn distance_to_segment(&self, a: (f64, f64), b: (f64, f64)) -> f64 {
+        let (ax, ay) = self.project(a);
+        let (mut bx, by) = self.project(b);
+        // the segment crosses the antipodal meridian, take the short way around
+        let half_circumference = 180.0 * self.cos_latitude * EARTH_RADIUS.to_radians();
+        if bx - ax > half_circumference {
+            bx -= 2.0 * half_circumference;
+        } else if ax - bx > half_circumference {
+            bx += 2.0 * half_circumference;
+        }
+        let (dx, dy) = (bx - ax, by - ay);
+        let length_squared = dx * dx + dy * dy;
+        let t = if length_squared > 0.0 {
+            (-(ax * dx + ay * dy) / length_squared).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (ax + t * dx).hypot(ay + t * dy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{latlon, polygon, rectangle};
+    use crate::CountryBoundariesBuilder;
+
+    /// meters of the given degrees of longitude at the given latitude
+    fn lon_meters(degrees: f64, latitude: f64) -> f64 {
+        degrees * latitude.to_radians().cos() * EARTH_RADIUS.to_radians()
+    }
+
+    fn assert_about(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < expected * 0.001,
+            "expected about {expected}, but was {actual}"
+        );
+    }
+
+    #[test]
+    fn nearest_ids_outside_of_region() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(2.0, 2.0, 8.0, 8.0)))
+            .build();
+
+        let nearest = boundaries.nearest_ids(latlon(5.0, 1.0), 200_000.0);
+        assert_eq!(1, nearest.len());
+        assert_eq!("A", nearest[0].0);
+        assert_about(lon_meters(1.0, 5.0), nearest[0].1);
+
+        assert!(boundaries
+            .nearest_ids(latlon(5.0, 1.0), 100_000.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn nearest_ids_are_sorted_by_distance() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(-5.0, 2.0, 5.0, 12.0)))
+            .add("B", polygon(rectangle(-5.0, -12.0, 5.0, -3.0)))
+            .add("C", polygon(rectangle(-5.0, -1.0, 5.0, 1.0)))
+            .build();
+
+        let nearest = boundaries.nearest_ids(latlon(0.0, 0.0), 500_000.0);
+        let ids: Vec<&str> = nearest.iter().map(|(id, _)| *id).collect();
+        assert_eq!(vec!["C", "A", "B"], ids);
+        assert_eq!(0.0, nearest[0].1);
+        assert_about(lon_meters(2.0, 0.0), nearest[1].1);
+        assert_about(lon_meters(3.0, 0.0), nearest[2].1);
+    }
+
+    #[test]
+    fn nearest_ids_across_180th_meridian() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, -179.0, 10.0, -170.0)))
+            .build();
+
+        let nearest = boundaries.nearest_ids(latlon(5.0, 179.5), 200_000.0);
+        assert_eq!(1, nearest.len());
+        assert_about(lon_meters(1.5, 5.0), nearest[0].1);
+    }
+
+    #[test]
+    fn distance_to_border_ignores_seams_between_cells() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 30.0, 30.0)))
+            .build();
+
+        // A covers several cells, the nearest cell edges are only 5° away
+        let distance = boundaries.distance_to_border(latlon(15.0, 15.0), "A");
+        assert_about(lon_meters(15.0, 15.0), distance.unwrap());
+    }
+
+    #[test]
+    fn distance_to_border_along_edge_of_cell() {
+        // A covers exactly one cell
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .build();
+
+        let distance = boundaries.distance_to_border(latlon(5.0, 4.0), "A");
+        assert_about(lon_meters(4.0, 5.0), distance.unwrap());
+        let distance = boundaries.distance_to_border(latlon(5.0, 12.0), "A");
+        assert_about(lon_meters(2.0, 5.0), distance.unwrap());
+    }
+
+    #[test]
+    fn distance_to_border_of_far_away_region() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .build();
+
+        let distance = boundaries
+            .distance_to_border(latlon(5.0, 60.0), "A")
+            .unwrap();
+        assert_about(lon_meters(50.0, 5.0), distance);
+    }
+
+    #[test]
+    fn distance_to_border_of_unknown_region() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .build();
+
+        assert_eq!(None, boundaries.distance_to_border(latlon(5.0, 5.0), "B"));
+    }
+
+    #[test]
+    fn distance_to_border_of_region_covering_the_world() {
+        let boundaries = CountryBoundariesBuilder::new(4, 2)
+            .unwrap()
+            .add("A", polygon(rectangle(-90.0, -180.0, 90.0, 180.0)))
+            .build();
+
+        assert_eq!(None, boundaries.distance_to_border(latlon(5.0, 5.0), "A"));
+    }
+}
@@ -0,0 +1,409 @@
+use crate::error::Error;
+use crate::{CountryBoundariesBuilder, LatLon, Multipolygon};
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+
+type Result<T> = std::result::Result<T, OsmXmlError>;
+
+#[derive(Debug)]
+pub enum OsmXmlError {
+    Xml(roxmltree::Error),
+    InvalidOsmXml(&'static str),
+    InvalidCoordinates(Error),
+    MissingElement { kind: &'static str, id: i64 },
+    UnclosedRing { relation: i64 },
+    Io(std::io::Error),
+}
+
+impl std::error::Error for OsmXmlError {}
+
+impl fmt::Display for OsmXmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OsmXmlError::Xml(e) => {
+                write!(f, "Unable to parse XML: {}", e)
+            }
+            OsmXmlError::InvalidOsmXml(reason) => {
+                write!(f, "Invalid OSM XML: {}", reason)
+            }
+            OsmXmlError::InvalidCoordinates(e) => {
+                write!(f, "Invalid coordinates: {}", e)
+            }
+            OsmXmlError::MissingElement { kind, id } => {
+                write!(f, "Referenced {kind} {id} is missing")
+            }
+            OsmXmlError::UnclosedRing { relation } => {
+                write!(f, "The rings of relation {relation} are not closed")
+            }
+            OsmXmlError::Io(e) => {
+                write!(f, "IO error: {}", e)
+            }
+        }
+    }
+}
+
+impl From<roxmltree::Error> for OsmXmlError {
+    fn from(error: roxmltree::Error) -> Self {
+        Self::Xml(error)
+    }
+}
+
+impl From<Error> for OsmXmlError {
+    fn from(error: Error) -> Self {
+        Self::InvalidCoordinates(error)
+    }
+}
+
+impl From<std::io::Error> for OsmXmlError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl CountryBoundariesBuilder {
+    /// Adds the geometries of all areas in the given [OSM XML](https://wiki.openstreetmap.org/wiki/OSM_XML)
+    /// to this builder.
+    ///
+    /// Areas are multipolygon (or boundary) relations and closed ways. The id of each area is
+    /// taken from the first of the given `id_tags` that the area is tagged with, e.g.
+    /// `["ISO3166-1:alpha2", "ISO3166-2"]` for the
+    /// [`boundaries.osm` of JOSM](https://josm.openstreetmap.de/export/HEAD/josm/trunk/resources/data/boundaries.osm)
+    /// that the default data is generated from. Areas that have none of the `id_tags` are ignored.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundariesBuilder, LatLon};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let osm = r#"<osm version="0.6">
+    ///   <node id="1" lat="0" lon="0"/>
+    ///   <node id="2" lat="0" lon="10"/>
+    ///   <node id="3" lat="10" lon="10"/>
+    ///   <way id="1">
+    ///     <nd ref="1"/><nd ref="2"/><nd ref="3"/><nd ref="1"/>
+    ///     <tag k="ISO3166-1:alpha2" v="XA"/>
+    ///   </way>
+    /// </osm>"#;
+    /// let boundaries = CountryBoundariesBuilder::new(360, 180)?
+    ///     .add_osm_xml(osm.as_bytes(), &["ISO3166-1:alpha2", "ISO3166-2"])?
+    ///     .build();
+    ///
+    /// assert_eq!(vec!["XA"], boundaries.ids(LatLon::new(1.0, 5.0)?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the given data is not valid OSM XML, if any node or way referenced by
+    /// a tagged area is missing, or if the ways of a relation do not form closed rings.
+    pub fn add_osm_xml(&mut self, mut reader: impl Read, id_tags: &[&str]) -> Result<&mut Self> {
+        let mut xml = String::new();
+        reader.read_to_string(&mut xml)?;
+        let document = Document::parse(&xml)?;
+
+        let mut nodes: HashMap<i64, LatLon> = HashMap::new();
+        let mut ways: HashMap<i64, Vec<i64>> = HashMap::new();
+        let mut areas: Vec<(String, Multipolygon<LatLon>)> = Vec::new();
+        let mut relations: Vec<(String, Node)> = Vec::new();
+
+        for element in document.root_element().children().filter(Node::is_element) {
+            match element.tag_name().name() {
+                "node" => {
+                    let latitude = parse_attribute(&element, "lat")?;
+                    let longitude = parse_attribute(&element, "lon")?;
+                    nodes.insert(
+                        parse_attribute(&element, "id")?,
+                        LatLon::new(latitude, longitude)?,
+                    );
+                }
+                "way" => {
+                    let id = parse_attribute(&element, "id")?;
+                    let node_ids = children(&element, "nd")
+                        .map(|nd| parse_attribute(&nd, "ref"))
+                        .collect::<Result<Vec<i64>>>()?;
+                    if let Some(area_id) = find_id(&element, id_tags) {
+                        if node_ids.len() > 3 && node_ids.first() == node_ids.last() {
+                            let ring = to_ring(&node_ids, &nodes)?;
+                            areas.push((
+                                area_id,
+                                Multipolygon {
+                                    outer: vec![ring],
+                                    inner: vec![],
+                                },
+                            ));
+                        }
+                    }
+                    ways.insert(id, node_ids);
+                }
+                "relation" => {
+                    if !matches!(tag(&element, "type"), Some("multipolygon" | "boundary")) {
+                        continue;
+                    }
+                    if let Some(area_id) = find_id(&element, id_tags) {
+                        relations.push((area_id, element));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (area_id, relation) in relations {
+            let relation_id = parse_attribute(&relation, "id")?;
+            let mut outer_ways: Vec<&[i64]> = Vec::new();
+            let mut inner_ways: Vec<&[i64]> = Vec::new();
+            for member in children(&relation, "member") {
+                if member.attribute("type") != Some("way") {
+                    continue;
+                }
+                let way_id = parse_attribute(&member, "ref")?;
+                let way = ways.get(&way_id).ok_or(OsmXmlError::MissingElement {
+                    kind: "way",
+                    id: way_id,
+                })?;
+                match member.attribute("role") {
+                    Some("inner") => inner_ways.push(way),
+                    _ => outer_ways.push(way),
+                }
+            }
+            let mut multipolygon = Multipolygon {
+                outer: vec![],
+                inner: vec![],
+            };
+            for (rings, ways) in [
+                (&mut multipolygon.outer, outer_ways),
+                (&mut multipolygon.inner, inner_ways),
+            ] {
+                let node_rings = assemble_rings(ways).ok_or(OsmXmlError::UnclosedRing {
+                    relation: relation_id,
+                })?;
+                for node_ids in node_rings {
+                    rings.push(to_ring(&node_ids, &nodes)?);
+                }
+            }
+            areas.push((area_id, multipolygon));
+        }
+
+        for (id, multipolygon) in areas {
+            self.add(id, multipolygon);
+        }
+        Ok(self)
+    }
+}
+
+/// Joins the given ways at their ends to closed rings. Returns `None` if that is not possible.
+fn assemble_rings(mut ways: Vec<&[i64]>) -> Option<Vec<Vec<i64>>> {
+    let mut rings = Vec::new();
+    while let Some(way) = ways.pop() {
+        let mut ring = way.to_vec();
+        while ring.first() != ring.last() {
+            let last = *ring.last()?;
+            let index = ways
+                .iter()
+                .position(|way| way.first() == Some(&last) || way.last() == Some(&last))?;
+            let next = ways.swap_remove(index);
+            if next.first() == Some(&last) {
+                ring.extend(&next[1..]);
+            } else {
+                ring.extend(next.iter().rev().skip(1));
+            }
+        }
+        rings.push(ring);
+    }
+    Some(rings)
+}
+
+fn to_ring(node_ids: &[i64], nodes: &HashMap<i64, LatLon>) -> Result<Vec<LatLon>> {
+    node_ids
+        .iter()
+        .map(|id| {
+            nodes.get(id).copied().ok_or(OsmXmlError::MissingElement {
+                kind: "node",
+                id: *id,
+            })
+        })
+        .collect()
+}
+
+fn find_id(element: &Node, id_tags: &[&str]) -> Option<String> {
+    id_tags
+        .iter()
+        .find_map(|key| tag(element, key))
+        .map(String::from)
+}
+
+fn tag<'a>(element: &Node<'a, '_>, key: &str) -> Option<&'a str> {
+    children(element, "tag")
+        .find(|tag| tag.attribute("k") == Some(key))
+        .and_then(|tag| tag.attribute("v"))
+}
+
+fn children<'a, 'input: 'a>(
+    element: &Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    element
+        .children()
+        .filter(move |child| child.tag_name().name() == name)
+}
+
+fn parse_attribute<T: std::str::FromStr>(element: &Node, name: &str) -> Result<T> {
+    element
+        .attribute(name)
+        .and_then(|value| value.parse().ok())
+        .ok_or(OsmXmlError::InvalidOsmXml(
+            "missing or invalid attribute of an element",
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::latlon;
+
+    fn build(osm: &str) -> Result<crate::CountryBoundaries> {
+        Ok(CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add_osm_xml(osm.as_bytes(), &["ISO3166-1:alpha2", "ISO3166-2"])?
+            .build())
+    }
+
+    // a square from 0,0 to 10,10 with a hole from 4,4 to 6,6
+    const NODES: &str = r#"
+        <node id="1" lat="0" lon="0"/>
+        <node id="2" lat="0" lon="10"/>
+        <node id="3" lat="10" lon="10"/>
+        <node id="4" lat="10" lon="0"/>
+        <node id="5" lat="4" lon="4"/>
+        <node id="6" lat="4" lon="6"/>
+        <node id="7" lat="6" lon="6"/>
+        <node id="8" lat="6" lon="4"/>
+    "#;
+
+    #[test]
+    fn assemble_rings_from_ways() {
+        assert_eq!(
+            Some(vec![vec![1, 2, 3, 1]]),
+            assemble_rings(vec![&[1, 2, 3, 1][..]])
+        );
+        assert_eq!(
+            Some(vec![vec![3, 1, 2, 3]]),
+            assemble_rings(vec![&[1, 2][..], &[2, 3], &[3, 1]])
+        );
+        // reversed way
+        assert_eq!(
+            Some(vec![vec![3, 1, 2, 3]]),
+            assemble_rings(vec![&[1, 2][..], &[3, 2], &[3, 1]])
+        );
+        assert_eq!(None, assemble_rings(vec![&[1, 2][..], &[2, 3]]));
+        assert_eq!(Some(vec![]), assemble_rings(vec![]));
+    }
+
+    #[test]
+    fn add_closed_way() {
+        let boundaries = build(&format!(
+            r#"<osm>{NODES}
+            <way id="1">
+                <nd ref="1"/><nd ref="2"/><nd ref="3"/><nd ref="4"/><nd ref="1"/>
+                <tag k="ISO3166-2" v="XA-B"/>
+            </way>
+            <way id="2">
+                <nd ref="5"/><nd ref="6"/><nd ref="7"/><nd ref="8"/><nd ref="5"/>
+            </way>
+            </osm>"#
+        ))
+        .unwrap();
+        assert_eq!(vec!["XA-B"], boundaries.ids(latlon(5.0, 5.0)));
+        assert!(boundaries.ids(latlon(11.0, 5.0)).is_empty());
+    }
+
+    #[test]
+    fn add_multipolygon_relation() {
+        let boundaries = build(&format!(
+            r#"<osm>{NODES}
+            <way id="1"><nd ref="1"/><nd ref="2"/><nd ref="3"/></way>
+            <way id="2"><nd ref="1"/><nd ref="4"/><nd ref="3"/></way>
+            <way id="3"><nd ref="5"/><nd ref="6"/><nd ref="7"/><nd ref="8"/><nd ref="5"/></way>
+            <relation id="1">
+                <member type="way" ref="1" role="outer"/>
+                <member type="way" ref="2" role="outer"/>
+                <member type="way" ref="3" role="inner"/>
+                <tag k="type" v="multipolygon"/>
+                <tag k="ISO3166-1:alpha2" v="XA"/>
+                <tag k="ISO3166-2" v="XA-B"/>
+            </relation>
+            </osm>"#
+        ))
+        .unwrap();
+        assert_eq!(vec!["XA"], boundaries.ids(latlon(2.0, 2.0)));
+        assert!(boundaries.ids(latlon(5.0, 5.0)).is_empty());
+    }
+
+    #[test]
+    fn ignore_areas_without_id() {
+        let boundaries = build(&format!(
+            r#"<osm>{NODES}
+            <way id="1">
+                <nd ref="1"/><nd ref="2"/><nd ref="3"/><nd ref="4"/><nd ref="1"/>
+            </way>
+            <relation id="1">
+                <member type="way" ref="1" role="outer"/>
+                <tag k="type" v="multipolygon"/>
+            </relation>
+            <relation id="2">
+                <member type="way" ref="1" role="outer"/>
+                <tag k="type" v="route"/>
+                <tag k="ISO3166-1:alpha2" v="XA"/>
+            </relation>
+            </osm>"#
+        ))
+        .unwrap();
+        assert!(boundaries.ids(latlon(5.0, 5.0)).is_empty());
+    }
+
+    #[test]
+    fn unclosed_ring_is_an_error() {
+        assert!(build(&format!(
+            r#"<osm>{NODES}
+            <way id="1"><nd ref="1"/><nd ref="2"/><nd ref="3"/></way>
+            <relation id="1">
+                <member type="way" ref="1" role="outer"/>
+                <tag k="type" v="multipolygon"/>
+                <tag k="ISO3166-1:alpha2" v="XA"/>
+            </relation>
+            </osm>"#
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn missing_elements_are_an_error() {
+        assert!(build(
+            r#"<osm>
+            <way id="1">
+                <nd ref="1"/><nd ref="2"/><nd ref="3"/><nd ref="1"/>
+                <tag k="ISO3166-1:alpha2" v="XA"/>
+            </way>
+            </osm>"#
+        )
+        .is_err());
+        assert!(build(
+            r#"<osm>
+            <relation id="1">
+                <member type="way" ref="1" role="outer"/>
+                <tag k="type" v="multipolygon"/>
+                <tag k="ISO3166-1:alpha2" v="XA"/>
+            </relation>
+            </osm>"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn invalid_xml_is_an_error() {
+        assert!(build("<osm>").is_err());
+        assert!(build(r#"<osm><node id="1" lat="a" lon="0"/></osm>"#).is_err());
+        assert!(build(r#"<osm><node id="1" lat="91" lon="0"/></osm>"#).is_err());
+    }
+}
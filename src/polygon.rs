@@ -0,0 +1,369 @@
+use crate::cell::Cell;
+use crate::clip::{area, clip_ring_to_rect, intersection_area, XY};
+use crate::grid::normalize;
+use crate::multipolygon::{Multipolygon, Point};
+use crate::region::RegionId;
+use crate::{CountryBoundaries, LatLon};
+use std::collections::HashSet;
+
+/// The part of a polygon within one cell, see [`CountryBoundaries::cells_and_local_polygons`]
+pub struct LocalPolygon<'a> {
+    /// the row of the cell
+    pub y: usize,
+    pub cell: &'a Cell,
+    /// the rings in local coordinates of the cell, the outer ring in counter-clockwise order
+    /// and the holes in clockwise order
+    pub rings: Vec<Vec<XY>>,
+    /// the area in local coordinates
+    pub area: f64,
+}
+
+impl CountryBoundaries {
+    /// Returns the ids of the regions that fully contain the given `polygon`.
+    ///
+    /// The polygon is given as a ring of positions, which does not need to be closed. Its edges
+    /// are straight lines in latitude and longitude, taking the shorter way around the globe,
+    /// i.e. it may cross the 180th meridian. It must not contain a pole.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
+    /// # use std::collections::HashSet;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // a triangle around Dallas
+    /// let polygon = [
+    ///     LatLon::new(32.6, -97.2)?,
+    ///     LatLon::new(32.6, -96.5)?,
+    ///     LatLon::new(33.1, -96.8)?,
+    /// ];
+    /// assert_eq!(
+    ///     HashSet::from(["US-TX", "US"]),
+    ///     boundaries.containing_ids_polygon(&polygon)
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn containing_ids_polygon(&self, polygon: &[LatLon]) -> HashSet<&str> {
+        self.containing_ids_rings(&[polygon])
+    }
+
+    /// Returns the ids of the regions that fully contain the polygon with the given `rings`,
+    /// the first being the outer ring, the others holes
+    pub(crate) fn containing_ids_rings(&self, rings: &[&[LatLon]]) -> HashSet<&str> {
+        let mut ids: Option<Vec<RegionId>> = None;
+        for local in self.cells_and_local_polygons(rings) {
+            let covering = local.cell.containing_ids.iter().copied().chain(
+                local
+                    .cell
+                    .intersecting_areas
+                    .iter()
+                    .filter(|(_, area)| {
+                        covered_area_in_rings(area, &local.rings) >= local.area * (1.0 - 1e-9)
+                    })
+                    .map(|(id, _)| *id),
+            );
+            match &mut ids {
+                None => ids = Some(covering.collect()),
+                Some(ids) => {
+                    let covering: Vec<RegionId> = covering.collect();
+                    ids.retain(|id| covering.contains(id));
+                }
+            }
+            if ids.as_ref().is_some_and(Vec::is_empty) {
+                break;
+            }
+        }
+        ids.unwrap_or_default()
+            .into_iter()
+            .map(|id| self.regions.id(id))
+            .collect()
+    }
+
+    /// Returns the ids of the regions that contain or at least intersect with the given
+    /// `polygon`.
+    ///
+    /// See [`CountryBoundaries::containing_ids_polygon`] on how the polygon is given.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
+    /// # use std::collections::HashSet;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // a triangle from Strasbourg to Kehl and Offenburg
+    /// let polygon = [
+    ///     LatLon::new(48.58, 7.75)?,
+    ///     LatLon::new(48.57, 7.82)?,
+    ///     LatLon::new(48.47, 7.94)?,
+    /// ];
+    /// assert_eq!(
+    ///     HashSet::from(["FR", "DE"]),
+    ///     boundaries.intersecting_ids_polygon(&polygon)
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn intersecting_ids_polygon(&self, polygon: &[LatLon]) -> HashSet<&str> {
+        self.intersecting_ids_rings(&[polygon])
+    }
+
+    /// Returns the ids of the regions that contain or at least intersect with the polygon with
+    /// the given `rings`, the first being the outer ring, the others holes
+    pub(crate) fn intersecting_ids_rings(&self, rings: &[&[LatLon]]) -> HashSet<&str> {
+        let mut ids: HashSet<&str> = HashSet::new();
+        for local in self.cells_and_local_polygons(rings) {
+            let cell = local.cell;
+            ids.extend(cell.containing_ids.iter().map(|&id| self.regions.id(id)));
+            ids.extend(
+                cell.intersecting_areas
+                    .iter()
+                    .filter(|(_, area)| {
+                        covered_area_in_rings(area, &local.rings) > local.area * 1e-9
+                    })
+                    .map(|(id, _)| self.regions.id(*id)),
+            );
+        }
+        ids
+    }
+
+    /// Returns the parts of the polygon with the given `rings` within each cell it overlaps
+    /// with. The first ring is the outer ring, the others are holes.
+    pub(crate) fn cells_and_local_polygons(&self, rings: &[&[LatLon]]) -> Vec<LocalPolygon<'_>> {
+        let Some((outer, holes)) = rings.split_first() else {
+            return Vec::new();
+        };
+        if outer.len() < 3 {
+            return Vec::new();
+        }
+        let outer = unwrap_ring(outer, None, true);
+        let (mut min, mut max) = (outer[0], outer[0]);
+        for p in &outer {
+            min = XY {
+                x: min.x.min(p.x),
+                y: min.y.min(p.y),
+            };
+            max = XY {
+                x: max.x.max(p.x),
+                y: max.y.max(p.y),
+            };
+        }
+        let mut rings = vec![outer];
+        rings.extend(
+            holes
+                .iter()
+                .filter(|hole| hole.len() >= 3)
+                .map(|hole| unwrap_ring(hole, Some(min.x), false)),
+        );
+
+        let grid = self.grid();
+        let (cell_height, cell_width) = grid.cell_size();
+        let row = |latitude: f64| {
+            (((90.0 - latitude) / cell_height).floor() as usize).min(grid.height - 1)
+        };
+        let column = |longitude: f64| ((longitude + 180.0) / cell_width).floor() as i64;
+        let min_column = column(min.x);
+        let max_column = column(max.x).min(min_column + grid.width as i64 - 1);
+
+        let mut result = Vec::new();
+        for y in row(max.y)..=row(min.y) {
+            for column in min_column..=max_column {
+                let x = column.rem_euclid(grid.width as i64) as usize;
+                let (cell_latitude, _) = grid.cell_origin(x, y);
+                let cell_longitude = -180.0 + column as f64 * cell_width;
+                let size = 0xffff as f64;
+                let local_rings: Vec<Vec<XY>> = rings
+                    .iter()
+                    .map(|ring| {
+                        let local: Vec<XY> = ring
+                            .iter()
+                            .map(|p| XY {
+                                x: (p.x - cell_longitude) / cell_width * size,
+                                y: (p.y - cell_latitude) / cell_height * size,
+                            })
+                            .collect();
+                        clip_ring_to_rect(&local, XY { x: 0.0, y: 0.0 }, XY { x: size, y: size })
+                    })
+                    .filter(|ring| !ring.is_empty())
+                    .collect();
+                let area: f64 = local_rings.iter().map(|ring| area(ring)).sum();
+                if area > 0.0 {
+                    result.push(LocalPolygon {
+                        y,
+                        cell: self.cell(x, y),
+                        rings: local_rings,
+                        area,
+                    });
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Returns the given ring with its longitudes unwrapped so that it is continuous across the
+/// 180th meridian, counter-clockwise if `is_outer` and clockwise otherwise. The first longitude
+/// is moved to be at or after the given `min_longitude`, if any.
+fn unwrap_ring(ring: &[LatLon], min_longitude: Option<f64>, is_outer: bool) -> Vec<XY> {
+    let mut longitude = normalize(ring[0].longitude(), -180.0, 360.0);
+    if let Some(min_longitude) = min_longitude {
+        longitude = normalize(longitude, min_longitude, 360.0);
+    }
+    let mut previous = ring[0].longitude();
+    let mut result: Vec<XY> = Vec::with_capacity(ring.len());
+    for position in ring {
+        longitude += normalize(position.longitude() - previous, -180.0, 360.0);
+        previous = position.longitude();
+        result.push(XY {
+            x: longitude,
+            y: position.latitude(),
+        });
+    }
+    if (area(&result) > 0.0) != is_outer {
+        result.reverse();
+    }
+    result
+}
+
+/// Returns the area of the polygon with the given `rings` that is covered by the given
+/// multipolygon. Counter-clockwise rings count positive, clockwise rings negative.
+pub fn covered_area_in_rings(multipolygon: &Multipolygon<Point>, rings: &[Vec<XY>]) -> f64 {
+    let covered = |other: &Vec<Point>| {
+        let other: Vec<XY> = other.iter().map(|&p| XY::from(p)).collect();
+        // the rings of the multipolygon may have any orientation
+        let sign = area(&other).signum();
+        rings
+            .iter()
+            .map(|ring| intersection_area(&other, ring) * sign)
+            .sum::<f64>()
+    };
+    multipolygon.outer.iter().map(covered).sum::<f64>()
+        - multipolygon.inner.iter().map(covered).sum::<f64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::{latlon, polygon, rectangle};
+    use crate::{CountryBoundariesBuilder, Multipolygon};
+    use std::collections::HashSet;
+
+    #[test]
+    fn too_few_positions() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .build();
+        let line = [latlon(1.0, 1.0), latlon(2.0, 2.0)];
+        assert!(boundaries.intersecting_ids_polygon(&line).is_empty());
+        assert!(boundaries.containing_ids_polygon(&line).is_empty());
+    }
+
+    #[test]
+    fn polygon_within_region() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 25.0, 25.0)))
+            .add("B", polygon(rectangle(0.0, 25.0, 25.0, 50.0)))
+            .build();
+        let triangle = [latlon(5.0, 5.0), latlon(5.0, 24.0), latlon(24.0, 5.0)];
+
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.containing_ids_polygon(&triangle)
+        );
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.intersecting_ids_polygon(&triangle)
+        );
+        // orientation does not matter
+        let mut reversed = triangle;
+        reversed.reverse();
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.containing_ids_polygon(&reversed)
+        );
+    }
+
+    #[test]
+    fn polygon_across_regions() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 25.0, 25.0)))
+            .add("B", polygon(rectangle(0.0, 25.0, 25.0, 50.0)))
+            .build();
+        let triangle = [latlon(5.0, 5.0), latlon(5.0, 30.0), latlon(20.0, 5.0)];
+
+        assert!(boundaries.containing_ids_polygon(&triangle).is_empty());
+        assert_eq!(
+            HashSet::from(["A", "B"]),
+            boundaries.intersecting_ids_polygon(&triangle)
+        );
+    }
+
+    #[test]
+    fn concave_polygon_around_region() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(4.0, 4.0, 6.0, 6.0)))
+            .build();
+        // a U shape around A
+        let u = [
+            latlon(0.0, 0.0),
+            latlon(0.0, 9.0),
+            latlon(9.0, 9.0),
+            latlon(9.0, 7.0),
+            latlon(2.0, 7.0),
+            latlon(2.0, 3.0),
+            latlon(9.0, 3.0),
+            latlon(9.0, 0.0),
+        ];
+
+        assert!(boundaries.intersecting_ids_polygon(&u).is_empty());
+    }
+
+    #[test]
+    fn polygon_across_180th_meridian() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 165.0, 20.0, 195.0)))
+            .build();
+        let triangle = [latlon(5.0, 170.0), latlon(5.0, -170.0), latlon(15.0, 180.0)];
+
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.containing_ids_polygon(&triangle)
+        );
+        let triangle = [latlon(5.0, 170.0), latlon(5.0, -160.0), latlon(15.0, 180.0)];
+        assert!(boundaries.containing_ids_polygon(&triangle).is_empty());
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.intersecting_ids_polygon(&triangle)
+        );
+    }
+
+    #[test]
+    fn polygon_with_hole() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![rectangle(0.0, 0.0, 30.0, 30.0)],
+                    inner: vec![rectangle(10.0, 10.0, 20.0, 20.0)],
+                },
+            )
+            .build();
+
+        let within_hole = [latlon(12.0, 12.0), latlon(12.0, 18.0), latlon(18.0, 15.0)];
+        assert!(boundaries.intersecting_ids_polygon(&within_hole).is_empty());
+
+        let around_hole = rectangle(5.0, 5.0, 25.0, 25.0);
+        assert!(boundaries.containing_ids_polygon(&around_hole).is_empty());
+        assert_eq!(
+            HashSet::from(["A"]),
+            boundaries.intersecting_ids_polygon(&around_hole)
+        );
+    }
+}
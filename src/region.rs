@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+/// A compact handle for one region id of a [`CountryBoundaries`](crate::CountryBoundaries).
+///
+/// Queries with handles instead of string ids are faster because they only need to compare
+/// integers. A handle is only valid for the `CountryBoundaries` it was obtained from, see
+/// [`CountryBoundaries::region_id`](crate::CountryBoundaries::region_id).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RegionId(pub(crate) u32);
+
+impl RegionId {
+    pub(crate) fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A set of [`RegionId`]s, stored as a bitset.
+///
+/// # Example
+/// ```
+/// # use country_boundaries::{CountryBoundaries, LatLon, RegionIdSet, BOUNDARIES_ODBL_360X180};
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+/// let saturday_first: RegionIdSet = ["BD", "DJ", "IR", "PS"]
+///     .into_iter()
+///     .filter_map(|id| boundaries.region_id(id))
+///     .collect();
+/// assert!(!boundaries.is_in_any_region(LatLon::new(21.0, 96.0)?, &saturday_first));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionIdSet {
+    bits: Vec<u64>,
+}
+
+impl RegionIdSet {
+    /// Creates an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the given `region` to the set
+    pub fn insert(&mut self, region: RegionId) {
+        let (word, bit) = position(region);
+        if word >= self.bits.len() {
+            self.bits.resize(word + 1, 0);
+        }
+        self.bits[word] |= bit;
+    }
+
+    /// Removes the given `region` from the set
+    pub fn remove(&mut self, region: RegionId) {
+        let (word, bit) = position(region);
+        if let Some(bits) = self.bits.get_mut(word) {
+            *bits &= !bit;
+        }
+    }
+
+    /// Returns whether the set contains the given `region`
+    pub fn contains(&self, region: RegionId) -> bool {
+        let (word, bit) = position(region);
+        self.bits.get(word).is_some_and(|bits| bits & bit != 0)
+    }
+
+    /// Returns whether the set contains no region
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&bits| bits == 0)
+    }
+}
+
+impl FromIterator<RegionId> for RegionIdSet {
+    fn from_iter<T: IntoIterator<Item = RegionId>>(iter: T) -> Self {
+        let mut set = Self::new();
+        for region in iter {
+            set.insert(region);
+        }
+        set
+    }
+}
+
+fn position(region: RegionId) -> (usize, u64) {
+    (region.index() / 64, 1 << (region.index() % 64))
+}
+
+/// Table of all the region ids of a `CountryBoundaries`, indexed by `RegionId`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Regions {
+    /// the ids of the regions, in the order in which they were added
+    ids: Vec<String>,
+    /// the sizes of the regions. Regions that have only been referred to in the raster have no
+    /// size
+    sizes: Vec<Option<f64>>,
+    lookup: HashMap<String, RegionId>,
+}
+
+impl Regions {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ids: Vec::with_capacity(capacity),
+            sizes: Vec::with_capacity(capacity),
+            lookup: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the handle for the given `id`, adds it if it doesn't exist yet
+    pub fn insert(&mut self, id: &str) -> RegionId {
+        if let Some(&region) = self.lookup.get(id) {
+            return region;
+        }
+        let region = RegionId(self.ids.len() as u32);
+        self.ids.push(String::from(id));
+        self.sizes.push(None);
+        self.lookup.insert(String::from(id), region);
+        region
+    }
+
+    pub fn set_size(&mut self, region: RegionId, size: f64) {
+        self.sizes[region.index()] = Some(size);
+    }
+
+    pub fn get(&self, id: &str) -> Option<RegionId> {
+        self.lookup.get(id).copied()
+    }
+
+    pub fn id(&self, region: RegionId) -> &str {
+        &self.ids[region.index()]
+    }
+
+    pub fn size(&self, region: RegionId) -> f64 {
+        self.sizes[region.index()].unwrap_or(0.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns the ids of all regions
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.ids.iter().map(String::as_str)
+    }
+
+    /// Returns the ids and sizes of all regions that have a size
+    pub fn sizes(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.ids
+            .iter()
+            .zip(self.sizes.iter())
+            .filter_map(|(id, size)| Some((id.as_str(), (*size)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_id_set() {
+        let mut set = RegionIdSet::new();
+        assert!(set.is_empty());
+        assert!(!set.contains(RegionId(0)));
+
+        set.insert(RegionId(3));
+        set.insert(RegionId(200));
+        assert!(!set.is_empty());
+        assert!(set.contains(RegionId(3)));
+        assert!(set.contains(RegionId(200)));
+        assert!(!set.contains(RegionId(4)));
+        assert!(!set.contains(RegionId(1000)));
+
+        set.remove(RegionId(3));
+        set.remove(RegionId(1000));
+        assert!(!set.contains(RegionId(3)));
+        set.remove(RegionId(200));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn region_id_set_from_iterator() {
+        let set: RegionIdSet = [RegionId(1), RegionId(64)].into_iter().collect();
+        assert!(set.contains(RegionId(1)));
+        assert!(set.contains(RegionId(64)));
+        assert!(!set.contains(RegionId(0)));
+    }
+
+    #[test]
+    fn insert_regions() {
+        let mut regions = Regions::default();
+        let a = regions.insert("A");
+        let b = regions.insert("B");
+        assert_ne!(a, b);
+        assert_eq!(a, regions.insert("A"));
+        assert_eq!(2, regions.len());
+        assert_eq!(Some(b), regions.get("B"));
+        assert_eq!(None, regions.get("C"));
+        assert_eq!("A", regions.id(a));
+        assert_eq!("B", regions.id(b));
+        assert_eq!(vec!["A", "B"], regions.ids().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn region_sizes() {
+        let mut regions = Regions::default();
+        let a = regions.insert("A");
+        let b = regions.insert("B");
+        let c = regions.insert("C");
+        regions.set_size(c, 3.0);
+        regions.set_size(a, 1.0);
+        assert_eq!(1.0, regions.size(a));
+        assert_eq!(0.0, regions.size(b));
+        assert_eq!(
+            vec![("A", 1.0), ("C", 3.0)],
+            regions.sizes().collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use crate::clip::XY;
+use crate::grid::normalize;
+use crate::region::RegionId;
+use crate::{CountryBoundaries, LatLon};
+
+/// Whether a route enters or exits a region, see [`CountryBoundaries::ids_along`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CrossingKind {
+    Enter,
+    Exit,
+}
+
+/// A point at which a route enters or exits a region, see [`CountryBoundaries::ids_along`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Crossing<'a> {
+    /// id of the region that is entered or exited
+    pub id: &'a str,
+    /// whether the region is entered or exited
+    pub kind: CrossingKind,
+    /// where the region is entered or exited
+    pub position: LatLon,
+    /// index of the segment of the route on which the region is entered or exited, i.e. the
+    /// crossing is between `path[segment]` and `path[segment + 1]`
+    pub segment: usize,
+}
+
+impl CountryBoundaries {
+    /// Returns where the route along the given `path` enters and exits which regions, in the
+    /// order in which they are passed.
+    ///
+    /// The regions in which the path starts are entered at its first position. Regions are not
+    /// exited at the last position of the path. If the route crosses several borders at the
+    /// same position, the regions are exited from the smallest to the largest and entered from
+    /// the largest to the smallest, e.g. `US-TX` is exited before `US`.
+    ///
+    /// Each segment of the path is a straight line in latitude and longitude, taking the shorter
+    /// way around the globe, i.e. it may cross the 180th meridian.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, CrossingKind, LatLon, BOUNDARIES_ODBL_360X180};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180)?;
+    /// // from Strasbourg across the Rhine to Kehl
+    /// let path = [LatLon::new(48.58, 7.75)?, LatLon::new(48.57, 7.82)?];
+    /// let crossings: Vec<(&str, CrossingKind)> = boundaries
+    ///     .ids_along(&path)
+    ///     .into_iter()
+    ///     .map(|crossing| (crossing.id, crossing.kind))
+    ///     .collect();
+    /// assert_eq!(
+    ///     vec![
+    ///         ("FR", CrossingKind::Enter),
+    ///         ("FR", CrossingKind::Exit),
+    ///         ("DE", CrossingKind::Enter),
+    ///     ],
+    ///     crossings
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ids_along(&self, path: &[LatLon]) -> Vec<Crossing<'_>> {
+        let Some(&start) = path.first() else {
+            return Vec::new();
+        };
+        let mut crossings = Vec::new();
+        let mut current = Vec::new();
+        self.add_crossings(
+            &mut crossings,
+            &mut current,
+            self.region_ids(start),
+            start,
+            0,
+        );
+
+        for (segment, pair) in path.windows(2).enumerate() {
+            let from = pair[0];
+            let delta_latitude = pair[1].latitude() - from.latitude();
+            let delta_longitude = normalize(pair[1].longitude() - from.longitude(), -180.0, 360.0);
+            let at = |t: f64| {
+                let latitude = from.latitude() + t * delta_latitude;
+                let longitude = from.longitude() + t * delta_longitude;
+                LatLon::new(
+                    latitude.clamp(-90.0, 90.0),
+                    normalize(longitude, -180.0, 360.0),
+                )
+                .expect("latitude is clamped")
+            };
+
+            let breaks = self.segment_breaks(from, delta_latitude, delta_longitude);
+            for pair in breaks.windows(2) {
+                let regions = self.region_ids(at((pair[0] + pair[1]) / 2.0));
+                if regions != current {
+                    self.add_crossings(&mut crossings, &mut current, regions, at(pair[0]), segment);
+                }
+            }
+        }
+        crossings
+    }
+
+    /// Returns the positions on the segment from `from` by the given deltas, as fractions of the
+    /// segment, at which the regions the segment is in may change: Where the segment crosses
+    /// from one cell into the next and where it crosses any geometry in a cell. Includes the
+    /// start and end of the segment.
+    fn segment_breaks(&self, from: LatLon, delta_latitude: f64, delta_longitude: f64) -> Vec<f64> {
+        let grid = self.grid();
+        let (cell_height, cell_width) = grid.cell_size();
+        let mut breaks = vec![0.0, 1.0];
+
+        // crossings of the cell borders
+        let lines = |start: f64, delta: f64, origin: f64, size: f64| {
+            let (min, max) = (start.min(start + delta), start.max(start + delta));
+            let first = ((min - origin) / size).ceil() as i64;
+            let last = ((max - origin) / size).floor() as i64;
+            (first..=last)
+                .filter(move |_| delta != 0.0)
+                .map(move |i| (origin + i as f64 * size - start) / delta)
+        };
+        breaks.extend(lines(from.latitude(), delta_latitude, -90.0, cell_height));
+        breaks.extend(lines(from.longitude(), delta_longitude, -180.0, cell_width));
+        breaks.retain(|t| (0.0..=1.0).contains(t));
+        sort(&mut breaks);
+
+        // crossings of the geometry within each cell
+        let mut geometry_breaks = Vec::new();
+        for pair in breaks.windows(2) {
+            let (t0, t1) = (pair[0], pair[1]);
+            let longitude = |t: f64| from.longitude() + t * delta_longitude;
+            let latitude = |t: f64| from.latitude() + t * delta_latitude;
+            let middle = (t0 + t1) / 2.0;
+            let middle_longitude = normalize(longitude(middle), -180.0, 360.0);
+            let position = LatLon::new(latitude(middle).clamp(-90.0, 90.0), middle_longitude)
+                .expect("latitude is clamped");
+            let (x, y, _) = grid.cell_and_local_point(position);
+            let cell = self.cell(x, y);
+            if cell.intersecting_areas.is_empty() {
+                continue;
+            }
+            // the segment may extend beyond the 180th meridian, the cell then needs to be moved
+            let (cell_latitude, cell_longitude) = grid.cell_origin(x, y);
+            let cell_longitude = cell_longitude + longitude(middle) - middle_longitude;
+            let local = |t: f64| XY {
+                x: (longitude(t) - cell_longitude) / cell_width * 0xffff as f64,
+                y: (latitude(t) - cell_latitude) / cell_height * 0xffff as f64,
+            };
+            let (p0, p1) = (local(t0), local(t1));
+
+            for (_, area) in &cell.intersecting_areas {
+                for ring in area.outer.iter().chain(area.inner.iter()) {
+                    let next = ring.iter().skip(1).chain(ring.first());
+                    for (u, v) in ring.iter().zip(next) {
+                        if let Some(s) = intersection(p0, p1, (*u).into(), (*v).into()) {
+                            geometry_breaks.push(t0 + s * (t1 - t0));
+                        }
+                    }
+                }
+            }
+        }
+        breaks.extend(geometry_breaks);
+        sort(&mut breaks);
+        breaks
+    }
+
+    /// Adds the crossings from the `current` regions to the given `regions` at `position` and
+    /// updates `current`
+    fn add_crossings<'a>(
+        &'a self,
+        crossings: &mut Vec<Crossing<'a>>,
+        current: &mut Vec<RegionId>,
+        regions: Vec<RegionId>,
+        position: LatLon,
+        segment: usize,
+    ) {
+        let crossing = |region: &RegionId, kind: CrossingKind| Crossing {
+            id: self.regions.id(*region),
+            kind,
+            position,
+            segment,
+        };
+        // regions are sorted by size ascending
+        crossings.extend(
+            current
+                .iter()
+                .filter(|region| !regions.contains(region))
+                .map(|region| crossing(region, CrossingKind::Exit)),
+        );
+        crossings.extend(
+            regions
+                .iter()
+                .rev()
+                .filter(|region| !current.contains(region))
+                .map(|region| crossing(region, CrossingKind::Enter)),
+        );
+        *current = regions;
+    }
+}
+
+/// Sorts the given fractions and removes (almost) duplicates
+fn sort(values: &mut Vec<f64>) {
+    values.sort_by(f64::total_cmp);
+    values.dedup_by(|a, b| (*a - *b).abs() < 1e-12);
+}
+
+/// Returns where the segment from `p0` to `p1` intersects with the segment from `u` to `v`, as a
+/// fraction of the former, if it does
+fn intersection(p0: XY, p1: XY, u: XY, v: XY) -> Option<f64> {
+    let cross = |a: XY, b: XY| a.x * b.y - a.y * b.x;
+    let r = XY {
+        x: p1.x - p0.x,
+        y: p1.y - p0.y,
+    };
+    let s = XY {
+        x: v.x - u.x,
+        y: v.y - u.y,
+    };
+    let q = XY {
+        x: u.x - p0.x,
+        y: u.y - p0.y,
+    };
+    let denominator = cross(r, s);
+    if denominator == 0.0 {
+        return None;
+    }
+    let t = cross(q, s) / denominator;
+    let w = cross(q, r) / denominator;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&w)).then_some(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{latlon, polygon, rectangle};
+    use crate::{CountryBoundariesBuilder, Multipolygon};
+
+    fn assert_crossings(expected: &[(&str, CrossingKind, f64, f64)], actual: &[Crossing]) {
+        assert_eq!(expected.len(), actual.len(), "{actual:?}");
+        for (&(id, kind, latitude, longitude), crossing) in expected.iter().zip(actual) {
+            assert_eq!((id, kind), (crossing.id, crossing.kind), "{actual:?}");
+            assert!(
+                (crossing.position.latitude() - latitude).abs() < 1e-3
+                    && (crossing.position.longitude() - longitude).abs() < 1e-3,
+                "{actual:?}"
+            );
+        }
+    }
+
+    use CrossingKind::{Enter, Exit};
+
+    #[test]
+    fn empty_path() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18).unwrap().build();
+        assert!(boundaries.ids_along(&[]).is_empty());
+    }
+
+    #[test]
+    fn path_of_one_position() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .build();
+        assert_crossings(
+            &[("A", Enter, 5.0, 5.0)],
+            &boundaries.ids_along(&[latlon(5.0, 5.0)]),
+        );
+    }
+
+    #[test]
+    fn path_across_regions() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 3.0, 10.0, 13.0)))
+            .add("B", polygon(rectangle(0.0, 13.0, 10.0, 27.0)))
+            .build();
+
+        let crossings = boundaries.ids_along(&[latlon(5.0, -5.0), latlon(5.0, 35.0)]);
+        assert_crossings(
+            &[
+                ("A", Enter, 5.0, 3.0),
+                ("A", Exit, 5.0, 13.0),
+                ("B", Enter, 5.0, 13.0),
+                ("B", Exit, 5.0, 27.0),
+            ],
+            &crossings,
+        );
+        assert!(crossings.iter().all(|crossing| crossing.segment == 0));
+    }
+
+    #[test]
+    fn path_of_several_segments() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .build();
+
+        let crossings = boundaries.ids_along(&[
+            latlon(5.0, 5.0),
+            latlon(5.0, 15.0),
+            latlon(15.0, 15.0),
+            latlon(5.0, 5.0),
+        ]);
+        assert_crossings(
+            &[
+                ("A", Enter, 5.0, 5.0),
+                ("A", Exit, 5.0, 10.0),
+                ("A", Enter, 10.0, 10.0),
+            ],
+            &crossings,
+        );
+        assert_eq!(
+            vec![0, 0, 2],
+            crossings.iter().map(|c| c.segment).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn path_across_nested_regions() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 30.0)))
+            .add("A-1", polygon(rectangle(0.0, 0.0, 10.0, 12.0)))
+            .add("A-2", polygon(rectangle(0.0, 12.0, 10.0, 30.0)))
+            .build();
+
+        assert_crossings(
+            &[
+                ("A", Enter, 5.0, 0.0),
+                ("A-1", Enter, 5.0, 0.0),
+                ("A-1", Exit, 5.0, 12.0),
+                ("A-2", Enter, 5.0, 12.0),
+                ("A-2", Exit, 5.0, 30.0),
+                ("A", Exit, 5.0, 30.0),
+            ],
+            &boundaries.ids_along(&[latlon(5.0, -5.0), latlon(5.0, 35.0)]),
+        );
+    }
+
+    #[test]
+    fn path_across_180th_meridian() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 175.0, 10.0, 185.0)))
+            .build();
+
+        assert_crossings(
+            &[("A", Enter, 3.0, 175.0), ("A", Exit, 7.0, -175.0)],
+            &boundaries.ids_along(&[latlon(1.0, 170.0), latlon(9.0, -170.0)]),
+        );
+    }
+
+    #[test]
+    fn path_across_hole() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add(
+                "A",
+                Multipolygon {
+                    outer: vec![polygon(rectangle(0.0, 0.0, 40.0, 40.0)).outer.remove(0)],
+                    inner: vec![polygon(rectangle(10.0, 15.0, 30.0, 25.0)).outer.remove(0)],
+                },
+            )
+            .build();
+
+        assert_crossings(
+            &[
+                ("A", Enter, 20.0, 5.0),
+                ("A", Exit, 20.0, 15.0),
+                ("A", Enter, 20.0, 25.0),
+            ],
+            &boundaries.ids_along(&[latlon(20.0, 5.0), latlon(20.0, 35.0)]),
+        );
+    }
+
+    #[test]
+    fn path_along_border() {
+        let boundaries = CountryBoundariesBuilder::new(36, 18)
+            .unwrap()
+            .add("A", polygon(rectangle(0.0, 0.0, 10.0, 10.0)))
+            .build();
+
+        let crossings = boundaries.ids_along(&[latlon(-10.0, 10.0), latlon(20.0, 10.0)]);
+        // depending on the rounding of the border, the path is either always outside of A or
+        // enters and exits it
+        assert!(crossings.len().is_multiple_of(2), "{crossings:?}");
+    }
+}
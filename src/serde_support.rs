@@ -0,0 +1,124 @@
+use crate::{BoundingBox, LatLon};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "LatLon")]
+struct LatLonData {
+    latitude: f64,
+    longitude: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "BoundingBox")]
+struct BoundingBoxData {
+    min_latitude: f64,
+    min_longitude: f64,
+    max_latitude: f64,
+    max_longitude: f64,
+}
+
+impl Serialize for LatLon {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        LatLonData {
+            latitude: self.latitude(),
+            longitude: self.longitude(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Fails if the position is not valid, see [`LatLon::new`]
+impl<'de> Deserialize<'de> for LatLon {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = LatLonData::deserialize(deserializer)?;
+        LatLon::new(data.latitude, data.longitude).map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for BoundingBox {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BoundingBoxData {
+            min_latitude: self.min_latitude(),
+            min_longitude: self.min_longitude(),
+            max_latitude: self.max_latitude(),
+            max_longitude: self.max_longitude(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Fails if the bounding box is not valid, see [`BoundingBox::new`]
+impl<'de> Deserialize<'de> for BoundingBox {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = BoundingBoxData::deserialize(deserializer)?;
+        BoundingBox::new(
+            data.min_latitude,
+            data.min_longitude,
+            data.max_latitude,
+            data.max_longitude,
+        )
+        .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Crossing, CrossingKind, LatLon};
+
+    #[test]
+    fn serialize_latlon() {
+        let position = LatLon::new(52.5, 13.4).unwrap();
+        let json = serde_json::to_string(&position).unwrap();
+        assert_eq!(r#"{"latitude":52.5,"longitude":13.4}"#, json);
+        assert_eq!(position, serde_json::from_str::<LatLon>(&json).unwrap());
+    }
+
+    #[test]
+    fn deserialize_invalid_latlon() {
+        let result = serde_json::from_str::<LatLon>(r#"{"latitude":91.0,"longitude":0.0}"#);
+        assert!(result.unwrap_err().to_string().contains("latitude 91"));
+        assert!(serde_json::from_str::<LatLon>(r#"{"latitude":0.0}"#).is_err());
+    }
+
+    #[test]
+    fn serialize_bounding_box() {
+        let bounds = BoundingBox::new(10.0, 170.0, 20.0, -170.0).unwrap();
+        let json = serde_json::to_string(&bounds).unwrap();
+        assert_eq!(
+            r#"{"min_latitude":10.0,"min_longitude":170.0,"max_latitude":20.0,"max_longitude":-170.0}"#,
+            json
+        );
+        let bounds = serde_json::from_str::<BoundingBox>(&json).unwrap();
+        assert_eq!(
+            (10.0, 170.0, 20.0, -170.0),
+            (
+                bounds.min_latitude(),
+                bounds.min_longitude(),
+                bounds.max_latitude(),
+                bounds.max_longitude()
+            )
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_bounding_box() {
+        let json =
+            r#"{"min_latitude":20.0,"min_longitude":0.0,"max_latitude":10.0,"max_longitude":1.0}"#;
+        assert!(serde_json::from_str::<BoundingBox>(json).is_err());
+    }
+
+    #[test]
+    fn serialize_crossing() {
+        let crossing = Crossing {
+            id: "DE",
+            kind: CrossingKind::Enter,
+            position: LatLon::new(48.5, 7.8).unwrap(),
+            segment: 0,
+        };
+        assert_eq!(
+            r#"{"id":"DE","kind":"Enter","position":{"latitude":48.5,"longitude":7.8},"segment":0}"#,
+            serde_json::to_string(&crossing).unwrap()
+        );
+    }
+}
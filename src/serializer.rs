@@ -0,0 +1,343 @@
+use crate::cell::Cell;
+use crate::multipolygon::Multipolygon;
+use crate::multipolygon::Point;
+use crate::region::Regions;
+use crate::CountryBoundaries;
+use std::fmt;
+use std::io::Write;
+
+type Result<T> = std::result::Result<T, WriteError>;
+
+#[derive(Debug)]
+pub enum WriteError {
+    StringTooLong { length: usize },
+    TooManyElements { max: usize, actual: usize },
+    Io(std::io::Error),
+}
+
+impl std::error::Error for WriteError {}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WriteError::StringTooLong { length } => {
+                write!(
+                    f,
+                    "String of {length} bytes is too long, must be at most {} bytes",
+                    u16::MAX
+                )
+            }
+            WriteError::TooManyElements { max, actual } => {
+                write!(f, "Too many elements ({actual}), must be at most {max}")
+            }
+            WriteError::Io(e) => {
+                write!(f, "IO error: {}", e)
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for WriteError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Serialize a `CountryBoundaries` into an IO stream, in the same format that is read by
+/// [`from_reader`](crate::deserializer::from_reader).
+///
+/// When writing to a destination against which short writes are not efficient, such as a
+/// [`File`], you will want to apply your own buffering because this function will not buffer the
+/// output. See [`io::BufWriter`].
+pub fn to_writer(mut writer: impl Write, boundaries: &CountryBoundaries) -> Result<()> {
+    write_u16(&mut writer, 2)?;
+
+    let regions = &boundaries.regions;
+    write_usize32(&mut writer, regions.sizes().count())?;
+    for (id, size) in regions.sizes() {
+        write_string(&mut writer, id)?;
+        write_f64(&mut writer, size)?;
+    }
+
+    write_usize32(&mut writer, boundaries.raster_width)?;
+
+    write_usize32(&mut writer, boundaries.raster.len())?;
+    for cell in &boundaries.raster {
+        write_cell(&mut writer, cell, regions)?;
+    }
+
+    Ok(())
+}
+
+fn write_cell(writer: &mut impl Write, cell: &Cell, regions: &Regions) -> Result<()> {
+    write_usize8(writer, cell.containing_ids.len())?;
+    for &id in &cell.containing_ids {
+        write_string(writer, regions.id(id))?;
+    }
+    write_usize8(writer, cell.intersecting_areas.len())?;
+    for (id, multipolygon) in &cell.intersecting_areas {
+        write_areas(writer, regions.id(*id), multipolygon)?;
+    }
+    Ok(())
+}
+
+fn write_areas(
+    writer: &mut impl Write,
+    id: &str,
+    multipolygon: &Multipolygon<Point>,
+) -> Result<()> {
+    write_string(writer, id)?;
+    write_polygons(writer, &multipolygon.outer)?;
+    write_polygons(writer, &multipolygon.inner)?;
+    Ok(())
+}
+
+fn write_polygons(writer: &mut impl Write, polygons: &[Vec<Point>]) -> Result<()> {
+    write_usize8(writer, polygons.len())?;
+    for ring in polygons {
+        write_ring(writer, ring)?;
+    }
+    Ok(())
+}
+
+fn write_ring(writer: &mut impl Write, ring: &[Point]) -> Result<()> {
+    write_usize32(writer, ring.len())?;
+    for point in ring {
+        write_point(writer, *point)?;
+    }
+    Ok(())
+}
+
+fn write_point(writer: &mut impl Write, point: Point) -> Result<()> {
+    write_u16(writer, point.x)?;
+    write_u16(writer, point.y)?;
+    Ok(())
+}
+
+fn write_u8(writer: &mut impl Write, value: u8) -> Result<()> {
+    writer.write_all(&value.to_be_bytes())?;
+    Ok(())
+}
+
+fn write_usize8(writer: &mut impl Write, value: usize) -> Result<()> {
+    let value = u8::try_from(value).map_err(|_| WriteError::TooManyElements {
+        max: usize::from(u8::MAX),
+        actual: value,
+    })?;
+    write_u8(writer, value)
+}
+
+fn write_u16(writer: &mut impl Write, value: u16) -> Result<()> {
+    writer.write_all(&value.to_be_bytes())?;
+    Ok(())
+}
+
+fn write_i32(writer: &mut impl Write, value: i32) -> Result<()> {
+    writer.write_all(&value.to_be_bytes())?;
+    Ok(())
+}
+
+fn write_usize32(writer: &mut impl Write, value: usize) -> Result<()> {
+    let value = i32::try_from(value).map_err(|_| WriteError::TooManyElements {
+        max: i32::MAX as usize,
+        actual: value,
+    })?;
+    write_i32(writer, value)
+}
+
+fn write_f64(writer: &mut impl Write, value: f64) -> Result<()> {
+    writer.write_all(&value.to_be_bytes())?;
+    Ok(())
+}
+
+fn write_string(writer: &mut impl Write, value: &str) -> Result<()> {
+    let bytes = value.as_bytes();
+    let length = u16::try_from(bytes.len()).map_err(|_| WriteError::StringTooLong {
+        length: bytes.len(),
+    })?;
+    write_u16(writer, length)?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserializer::from_reader;
+
+    fn written<F: FnOnce(&mut Vec<u8>) -> Result<()>>(write: F) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_write_string() {
+        assert_eq!(vec![0x00, 0x00], written(|w| write_string(w, "")));
+        assert_eq!(vec![0x00, 0x01, 0x41], written(|w| write_string(w, "A")));
+        assert_eq!(
+            vec![0x00, 0x02, 0x41, 0x42],
+            written(|w| write_string(w, "AB"))
+        );
+
+        let too_long = "A".repeat(usize::from(u16::MAX) + 1);
+        assert!(write_string(&mut Vec::new(), &too_long).is_err());
+    }
+
+    #[test]
+    fn write_float() {
+        assert_eq!(
+            vec![0x40, 0x29, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            written(|w| write_f64(w, 12.5))
+        );
+    }
+
+    #[test]
+    fn test_write_usize8() {
+        assert_eq!(vec![0x11], written(|w| write_usize8(w, 17)));
+        assert_eq!(vec![0xff], written(|w| write_usize8(w, 255)));
+        assert!(write_usize8(&mut Vec::new(), 256).is_err());
+    }
+
+    #[test]
+    fn test_write_u16() {
+        assert_eq!(vec![0x00, 0x11], written(|w| write_u16(w, 17)));
+        assert_eq!(vec![0xff, 0xff], written(|w| write_u16(w, u16::MAX)));
+    }
+
+    #[test]
+    fn test_write_usize32() {
+        assert_eq!(
+            vec![0x00, 0x00, 0x00, 0x11],
+            written(|w| write_usize32(w, 17))
+        );
+        assert_eq!(
+            vec![0x7f, 0xff, 0xff, 0xff],
+            written(|w| write_usize32(w, i32::MAX as usize))
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn write_usize32_rejects_values_too_big() {
+        assert!(write_usize32(&mut Vec::new(), i32::MAX as usize + 1).is_err());
+    }
+
+    #[test]
+    fn test_write_ring() {
+        assert_eq!(
+            vec![
+                0x00, 0x00, 0x00, 0x02, // length
+                0x00, 0x01, // p1.x
+                0x00, 0x02, // p1.y
+                0x00, 0x03, // p2.x
+                0x00, 0x04, // p2.y
+            ],
+            written(|w| write_ring(w, &[Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]))
+        );
+    }
+
+    #[test]
+    fn test_write_cell() {
+        let mut regions = Regions::default();
+        let cell = Cell {
+            containing_ids: vec![regions.insert("A")],
+            intersecting_areas: vec![(
+                regions.insert("B"),
+                Multipolygon {
+                    outer: vec![],
+                    inner: vec![],
+                },
+            )],
+        };
+        assert_eq!(
+            vec![
+                0x01, // containing ids length
+                0x00, 0x01, 0x41, // "A"
+                0x01, // intersecting areas length
+                0x00, 0x01, 0x42, // "B"
+                0x00, 0x00, // empty multipolygon
+            ],
+            written(|w| write_cell(w, &cell, &regions))
+        );
+    }
+
+    #[test]
+    fn test_write_basic() {
+        let mut regions = Regions::default();
+        let a = regions.insert("A");
+        regions.set_size(a, 12.5);
+        let boundaries = CountryBoundaries {
+            raster: vec![Cell {
+                containing_ids: vec![a],
+                intersecting_areas: vec![],
+            }],
+            raster_width: 1,
+            regions,
+        };
+        let basic = vec![
+            0x00, 0x02, // version number
+            0x00, 0x00, 0x00, 0x01, // geometry sizes map length
+            0x00, 0x01, 0x41, // "A"
+            0x40, 0x29, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 12.5
+            0x00, 0x00, 0x00, 0x01, // raster width
+            0x00, 0x00, 0x00, 0x01, // raster size
+            0x01, // cell containing ids length
+            0x00, 0x01, 0x41, // "A"
+            0x00, // intersecting areas length
+        ];
+        assert_eq!(basic, written(|w| to_writer(w, &boundaries)));
+    }
+
+    #[test]
+    fn write_and_read_again() {
+        let mut regions = Regions::default();
+        let b = regions.insert("B");
+        let a = regions.insert("A");
+        regions.set_size(b, 1.0);
+        regions.set_size(a, 2.0);
+        let boundaries = CountryBoundaries {
+            raster: vec![
+                Cell {
+                    containing_ids: vec![a],
+                    intersecting_areas: vec![],
+                },
+                Cell {
+                    containing_ids: vec![],
+                    intersecting_areas: vec![(
+                        b,
+                        Multipolygon {
+                            outer: vec![vec![
+                                Point { x: 0, y: 0 },
+                                Point { x: 0, y: 2 },
+                                Point { x: 2, y: 2 },
+                            ]],
+                            inner: vec![vec![Point { x: 1, y: 1 }]],
+                        },
+                    )],
+                },
+            ],
+            raster_width: 2,
+            regions,
+        };
+        let bytes = written(|w| to_writer(w, &boundaries));
+        assert_eq!(boundaries, from_reader(bytes.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn ids_without_size_are_not_written_to_geometry_sizes() {
+        let mut regions = Regions::default();
+        let a = regions.insert("A");
+        let boundaries = CountryBoundaries {
+            raster: vec![Cell {
+                containing_ids: vec![a],
+                intersecting_areas: vec![],
+            }],
+            raster_width: 1,
+            regions,
+        };
+        let bytes = written(|w| to_writer(w, &boundaries));
+        assert_eq!([0x00, 0x00, 0x00, 0x00], bytes[2..6]);
+        assert_eq!(boundaries, from_reader(bytes.as_slice()).unwrap());
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{CountryBoundaries, ReadError};
+use arc_swap::ArcSwap;
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+/// A handle to [`CountryBoundaries`] that can be replaced with newly loaded data while it is in
+/// use, for long-running services that need to update the boundaries without restarting.
+///
+/// Clones share the same data. Reading the current data never blocks, not even while new data
+/// is swapped in, and data that has been replaced is dropped as soon as the last reader that
+/// still uses it is done with it.
+///
+/// # Example
+/// ```
+/// # use country_boundaries::{CountryBoundaries, LatLon, SharedBoundaries};
+/// # use country_boundaries::{BOUNDARIES_ODBL_360X180, BOUNDARIES_ODBL_60X30};
+/// #
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let shared = SharedBoundaries::new(CountryBoundaries::from_reader(BOUNDARIES_ODBL_60X30)?);
+///
+/// let boundaries = shared.load();
+/// assert_eq!(vec!["US-TX", "US"], boundaries.ids(LatLon::new(33.0, -97.0)?));
+///
+/// shared.reload_from(BOUNDARIES_ODBL_360X180)?;
+/// // boundaries still refers to the previous data, shared.load() returns the new data
+/// assert_eq!(360, shared.load().statistics().raster_width);
+/// assert_eq!(60, boundaries.statistics().raster_width);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedBoundaries {
+    current: Arc<ArcSwap<CountryBoundaries>>,
+}
+
+impl SharedBoundaries {
+    pub fn new(boundaries: CountryBoundaries) -> Self {
+        Self {
+            current: Arc::new(ArcSwap::from_pointee(boundaries)),
+        }
+    }
+
+    /// Returns the current data. It stays valid even if new data is swapped in in the meantime,
+    /// so load it once per request rather than holding on to it for the lifetime of the service.
+    pub fn load(&self) -> Arc<CountryBoundaries> {
+        self.current.load_full()
+    }
+
+    /// Replaces the current data with the given `boundaries`
+    pub fn store(&self, boundaries: CountryBoundaries) {
+        self.current.store(Arc::new(boundaries));
+    }
+
+    /// Reads new data from the given `reader` and replaces the current data with it.
+    ///
+    /// # Errors
+    /// If the given data is not a valid country boundaries file. The current data is kept then.
+    pub fn reload_from(&self, reader: impl io::Read) -> Result<(), ReadError> {
+        self.store(CountryBoundaries::from_reader(reader)?);
+        Ok(())
+    }
+
+    /// Reads new data from the file at `path` and replaces the current data with it.
+    ///
+    /// # Errors
+    /// If the file cannot be read or is not a valid country boundaries file. The current data is
+    /// kept then.
+    pub fn reload_from_file(&self, path: impl AsRef<Path>) -> Result<(), ReadError> {
+        self.reload_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Checks every `interval` whether the file at `path` changed (i.e. its modification time or
+    /// size) and if so, replaces the current data with the data read from it. `on_reload` is
+    /// called after each attempt to reload with whether it succeeded. If the file is not valid,
+    /// the current data is kept.
+    ///
+    /// To not read a file that is only partly written, replace the file atomically, i.e. write
+    /// the new data to another file and then rename it to `path`.
+    ///
+    /// The file is watched until the returned [`FileWatcher`] is dropped.
+    pub fn watch(
+        &self,
+        path: impl Into<PathBuf>,
+        interval: Duration,
+        mut on_reload: impl FnMut(Result<(), ReadError>) + Send + 'static,
+    ) -> FileWatcher {
+        let path = path.into();
+        let shared = self.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let mut last_version = file_version(&path);
+        let thread = thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                thread::park_timeout(interval);
+                if stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                let version = file_version(&path);
+                if version.is_some() && version != last_version {
+                    last_version = version;
+                    on_reload(shared.reload_from_file(&path));
+                }
+            }
+        });
+        FileWatcher {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl From<CountryBoundaries> for SharedBoundaries {
+    fn from(boundaries: CountryBoundaries) -> Self {
+        Self::new(boundaries)
+    }
+}
+
+/// Watches a file for changes, see [`SharedBoundaries::watch`]. Watching stops when it is
+/// dropped.
+#[derive(Debug)]
+pub struct FileWatcher {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Returns the modification time and size of the file at `path`, if it exists
+fn file_version(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{latlon, polygon, rectangle};
+    use crate::CountryBoundariesBuilder;
+    use std::sync::mpsc;
+
+    fn world(id: &str) -> CountryBoundaries {
+        CountryBoundariesBuilder::new(4, 2)
+            .unwrap()
+            .add(id, polygon(rectangle(-90.0, -180.0, 90.0, 180.0)))
+            .build()
+    }
+
+    fn bytes(boundaries: &CountryBoundaries) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        boundaries.write_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn ids(shared: &SharedBoundaries) -> Vec<String> {
+        let position = latlon(0.0, 0.0);
+        shared
+            .load()
+            .ids(position)
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn store_replaces_data_for_all_clones() {
+        let shared = SharedBoundaries::new(world("A"));
+        let clone = shared.clone();
+        let previous = shared.load();
+        clone.store(world("B"));
+        assert_eq!(vec!["B"], ids(&shared));
+        assert_eq!(world("A"), *previous);
+    }
+
+    #[test]
+    fn replaced_data_is_dropped_after_last_reader() {
+        let shared = SharedBoundaries::new(world("A"));
+        let previous = shared.load();
+        let weak = Arc::downgrade(&previous);
+        shared.store(world("B"));
+        assert!(weak.upgrade().is_some());
+        drop(previous);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn reload_keeps_data_if_invalid() {
+        let shared = SharedBoundaries::new(world("A"));
+        assert!(shared.reload_from([1u8, 2, 3].as_slice()).is_err());
+        assert!(shared.reload_from_file("does-not-exist.ser").is_err());
+        assert_eq!(vec!["A"], ids(&shared));
+        shared.reload_from(bytes(&world("B")).as_slice()).unwrap();
+        assert_eq!(vec!["B"], ids(&shared));
+    }
+
+    #[test]
+    fn watch_file() {
+        let dir = std::env::temp_dir().join(format!("country-boundaries-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("watched.ser");
+        fs::write(&path, bytes(&world("A"))).unwrap();
+
+        let shared = SharedBoundaries::new(world("A"));
+        let (sender, receiver) = mpsc::channel();
+        let watcher = shared.watch(&path, Duration::from_millis(10), move |result| {
+            let _ = sender.send(result.is_ok());
+        });
+        let timeout = Duration::from_secs(10);
+
+        // replaced atomically, with a different size so that the change is noticed even if the
+        // modification time has a coarse resolution
+        let replace = |bytes: &[u8]| {
+            let temp = dir.join("watched.ser.tmp");
+            fs::write(&temp, bytes).unwrap();
+            fs::rename(&temp, &path).unwrap();
+        };
+
+        let mut bytes_b = bytes(&world("BB"));
+        replace(&bytes_b);
+        assert!(receiver.recv_timeout(timeout).unwrap());
+        assert_eq!(vec!["BB"], ids(&shared));
+
+        bytes_b.truncate(3);
+        replace(&bytes_b);
+        assert!(!receiver.recv_timeout(timeout).unwrap());
+        assert_eq!(vec!["BB"], ids(&shared));
+
+        drop(watcher);
+        replace(&bytes(&world("CCC")));
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(vec!["BB"], ids(&shared));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
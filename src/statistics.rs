@@ -0,0 +1,144 @@
+use crate::CountryBoundaries;
+
+/// Statistics about the raster of a `CountryBoundaries`, see [`CountryBoundaries::statistics`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statistics {
+    /// width of the raster
+    pub raster_width: usize,
+    /// height of the raster
+    pub raster_height: usize,
+    /// number of different ids in the data
+    pub ids: usize,
+    /// number of cells in which no region is located at all
+    pub empty_cells: usize,
+    /// number of cells that contain any geometry, i.e. for which point-in-polygon checks are
+    /// necessary
+    pub cells_with_geometry: usize,
+    /// maximum number of ids found in any single cell
+    pub max_ids_per_cell: usize,
+    /// total number of points of all the geometry in the raster
+    pub points: usize,
+}
+
+impl CountryBoundaries {
+    /// Returns statistics about the raster, e.g. to judge the trade-off between the raster size
+    /// and the file size and speed. This is mostly useful when building own data with the
+    /// [`CountryBoundariesBuilder`](crate::CountryBoundariesBuilder) in a custom raster size or
+    /// to monitor what data a long-running service has currently loaded.
+    ///
+    /// # Example
+    /// ```
+    /// # use country_boundaries::{CountryBoundaries, BOUNDARIES_ODBL_60X30};
+    /// #
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_60X30)?;
+    /// let statistics = boundaries.statistics();
+    /// assert_eq!(60, statistics.raster_width);
+    /// assert_eq!(30, statistics.raster_height);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn statistics(&self) -> Statistics {
+        let mut statistics = Statistics {
+            raster_width: self.raster_width,
+            raster_height: self
+                .raster
+                .len()
+                .checked_div(self.raster_width)
+                .unwrap_or(0),
+            ids: self.regions.len(),
+            empty_cells: 0,
+            cells_with_geometry: 0,
+            max_ids_per_cell: 0,
+            points: 0,
+        };
+        for cell in &self.raster {
+            let ids = cell.containing_ids.len() + cell.intersecting_areas.len();
+            if ids == 0 {
+                statistics.empty_cells += 1;
+            }
+            if !cell.intersecting_areas.is_empty() {
+                statistics.cells_with_geometry += 1;
+            }
+            statistics.max_ids_per_cell = statistics.max_ids_per_cell.max(ids);
+            statistics.points += cell
+                .intersecting_areas
+                .iter()
+                .flat_map(|(_, area)| area.outer.iter().chain(area.inner.iter()))
+                .map(Vec::len)
+                .sum::<usize>();
+        }
+        statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+    use crate::multipolygon::{Multipolygon, Point};
+    use crate::region::Regions;
+
+    #[test]
+    fn statistics_of_empty_raster() {
+        let boundaries = CountryBoundaries {
+            raster: vec![],
+            raster_width: 0,
+            regions: Regions::default(),
+        };
+        assert_eq!(
+            Statistics {
+                raster_width: 0,
+                raster_height: 0,
+                ids: 0,
+                empty_cells: 0,
+                cells_with_geometry: 0,
+                max_ids_per_cell: 0,
+                points: 0,
+            },
+            boundaries.statistics()
+        );
+    }
+
+    #[test]
+    fn statistics_of_raster() {
+        let p = Point { x: 0, y: 0 };
+        let mut regions = Regions::default();
+        let a = regions.insert("A");
+        let b = regions.insert("B");
+        let boundaries = CountryBoundaries {
+            raster: vec![
+                Cell::default(),
+                Cell {
+                    containing_ids: vec![a],
+                    intersecting_areas: vec![(
+                        b,
+                        Multipolygon {
+                            outer: vec![vec![p, p, p], vec![p, p, p]],
+                            inner: vec![vec![p, p, p]],
+                        },
+                    )],
+                },
+                Cell {
+                    containing_ids: vec![a],
+                    intersecting_areas: vec![],
+                },
+                Cell::default(),
+            ],
+            raster_width: 2,
+            regions,
+        };
+        assert_eq!(
+            Statistics {
+                raster_width: 2,
+                raster_height: 2,
+                ids: 2,
+                empty_cells: 2,
+                cells_with_geometry: 1,
+                max_ids_per_cell: 2,
+                points: 9,
+            },
+            boundaries.statistics()
+        );
+    }
+}
@@ -1,7 +1,10 @@
-use country_boundaries::{self, BoundingBox, CountryBoundaries, LatLon, BOUNDARIES_ODBL_180X90};
+use country_boundaries::{
+    self, BoundingBox, CountryBoundaries, CountryBoundariesBuilder, CountryBoundariesRef,
+    CrossingKind, LatLon, RegionIdSet, BOUNDARIES_ODBL_180X90, BOUNDARIES_ODBL_360X180,
+    BOUNDARIES_ODBL_60X30,
+};
 use std::collections::HashSet;
 
-
 #[test]
 fn return_correct_results_at_cell_edges() {
     let boundaries = boundaries();
@@ -28,8 +31,175 @@ fn containing_ids_at_180th_meridian() {
     );
 }
 
+#[test]
+fn writing_default_data_results_in_identical_bytes() {
+    for data in [
+        BOUNDARIES_ODBL_60X30,
+        BOUNDARIES_ODBL_180X90,
+        BOUNDARIES_ODBL_360X180,
+    ] {
+        let mut bytes = Vec::with_capacity(data.len());
+        CountryBoundaries::from_reader(data)
+            .unwrap()
+            .write_to(&mut bytes)
+            .unwrap();
+        assert!(data == bytes.as_slice());
+    }
+}
+
+#[test]
+fn boundaries_ref_returns_same_results() {
+    let boundaries = boundaries();
+    let boundaries_ref = CountryBoundariesRef::from_bytes(BOUNDARIES_ODBL_180X90).unwrap();
+
+    for latitude in -89..90 {
+        for longitude in -180..180 {
+            let position = latlon(latitude as f64 + 0.25, longitude as f64 + 0.75);
+            assert_eq!(boundaries.ids(position), boundaries_ref.ids(position));
+        }
+    }
+    let bounds = BoundingBox::new(50.6, 5.9, 50.8, 6.1).unwrap();
+    assert_eq!(
+        boundaries.intersecting_ids(bounds),
+        boundaries_ref.intersecting_ids(bounds)
+    );
+    let bounds = BoundingBox::new(66.0, 178.0, 68.0, -178.0).unwrap();
+    assert_eq!(
+        boundaries.containing_ids(bounds),
+        boundaries_ref.containing_ids(bounds)
+    );
+}
+
+#[test]
+fn region_ids_return_same_results_as_ids() {
+    let boundaries = boundaries();
+    let ids = HashSet::from(["DE", "US-TX", "RU"]);
+    let regions: RegionIdSet = ids
+        .iter()
+        .map(|id| boundaries.region_id(id).unwrap())
+        .collect();
+    let texas = boundaries.region_id("US-TX").unwrap();
+
+    for latitude in -89..90 {
+        for longitude in -180..180 {
+            let position = latlon(latitude as f64 + 0.25, longitude as f64 + 0.75);
+            let region_ids: Vec<&str> = boundaries
+                .region_ids(position)
+                .into_iter()
+                .map(|region| boundaries.id_of(region).unwrap())
+                .collect();
+            assert_eq!(boundaries.ids(position), region_ids);
+            assert_eq!(
+                boundaries.is_in_any(position, &ids),
+                boundaries.is_in_any_region(position, &regions)
+            );
+            assert_eq!(
+                boundaries.is_in(position, "US-TX"),
+                boundaries.is_in_region(position, texas)
+            );
+        }
+    }
+}
+
+#[test]
+fn geometry_results_in_same_boundaries() {
+    let boundaries = boundaries();
+    let mut builder = CountryBoundariesBuilder::new(180, 90).unwrap();
+    for id in ["DE", "US-TX", "RU"] {
+        builder.add(id, boundaries.geometry(id).unwrap());
+    }
+    let rebuilt = builder.build();
+
+    for (id, min_latitude, min_longitude) in [("DE", 47, 5), ("US-TX", 25, -107), ("RU", 60, 170)] {
+        for latitude in 0..100 {
+            for longitude in 0..150 {
+                let position = latlon(
+                    min_latitude as f64 + latitude as f64 * 0.13 + 0.01,
+                    min_longitude as f64 + longitude as f64 * 0.13 + 0.01,
+                );
+                assert_eq!(
+                    boundaries.is_in(position, id),
+                    rebuilt.is_in(position, id),
+                    "{id} at {position}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn ids_along_agrees_with_ids() {
+    let boundaries = boundaries();
+    // from Lisbon to Moscow to Vladivostok, across the Bering Strait to Anchorage and Mexico City
+    let path = [
+        latlon(38.72, -9.14),
+        latlon(55.76, 37.62),
+        latlon(43.12, 131.89),
+        latlon(66.0, -170.0),
+        latlon(61.22, -149.9),
+        latlon(19.43, -99.13),
+    ];
+    let crossings = boundaries.ids_along(&path);
+
+    let mut current: Vec<&str> = Vec::new();
+    let mut segment = 0;
+    for crossing in crossings {
+        // at each position of the path, the regions entered but not exited are those it is in
+        while segment < crossing.segment {
+            segment += 1;
+            let mut ids = boundaries.ids(path[segment]);
+            ids.sort();
+            current.sort();
+            assert_eq!(ids, current, "at {}", path[segment]);
+        }
+        match crossing.kind {
+            CrossingKind::Enter => {
+                assert!(!current.contains(&crossing.id));
+                current.push(crossing.id);
+            }
+            CrossingKind::Exit => {
+                assert!(current.contains(&crossing.id));
+                current.retain(|id| *id != crossing.id);
+            }
+        }
+    }
+    let mut ids = boundaries.ids(path[path.len() - 1]);
+    ids.sort();
+    current.sort();
+    assert_eq!(ids, current);
+}
+
+#[test]
+fn polygon_queries_agree_with_exact_bounding_box_queries() {
+    let boundaries = boundaries();
+    for (min_latitude, min_longitude, max_latitude, max_longitude) in [
+        (50.75, 5.98, 50.76, 6.04),
+        (50.6, 5.9, 50.8, 6.1),
+        (47.0, 5.0, 55.0, 15.0),
+        (32.6, -97.2, 33.1, -96.5),
+        (66.0, 178.0, 68.0, -178.0),
+    ] {
+        let bounds = BoundingBox::new(min_latitude, min_longitude, max_latitude, max_longitude);
+        let bounds = bounds.unwrap();
+        let polygon = [
+            latlon(min_latitude, min_longitude),
+            latlon(min_latitude, max_longitude),
+            latlon(max_latitude, max_longitude),
+            latlon(max_latitude, min_longitude),
+        ];
+        assert_eq!(
+            boundaries.intersecting_ids_exact(bounds),
+            boundaries.intersecting_ids_polygon(&polygon)
+        );
+        assert_eq!(
+            boundaries.containing_ids_exact(bounds),
+            boundaries.containing_ids_polygon(&polygon)
+        );
+    }
+}
+
 fn boundaries() -> CountryBoundaries {
-    return CountryBoundaries::from_reader(BOUNDARIES_ODBL_180X90).unwrap();
+    CountryBoundaries::from_reader(BOUNDARIES_ODBL_180X90).unwrap()
 }
 
 fn latlon(latitude: f64, longitude: f64) -> LatLon {
@@ -0,0 +1,109 @@
+//! WebAssembly bindings for country-boundaries, to query the boundaries in the browser or in
+//! Node.js.
+//!
+//! Build with `wasm-pack build --target web wasm` and run the tests in Node.js with
+//! `wasm-pack test --node wasm` or, with `wasm-bindgen-cli` installed, with
+//! `CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner cargo test -p country-boundaries-wasm --target wasm32-unknown-unknown`.
+//!
+//! ```js
+//! import init, { CountryBoundaries } from "./pkg/country_boundaries_wasm.js";
+//!
+//! await init();
+//! const boundaries = CountryBoundaries.fromBytes(new Uint8Array(await response.arrayBuffer()));
+//! boundaries.ids(33.0, -97.0); // ["US-TX", "US"]
+//! ```
+//!
+//! Sets of ids are returned as arrays sorted alphabetically.
+
+use country_boundaries::{BoundingBox, LatLon, BOUNDARIES_ODBL_60X30};
+use std::collections::HashSet;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct CountryBoundaries(country_boundaries::CountryBoundaries);
+
+#[wasm_bindgen]
+impl CountryBoundaries {
+    /// Loads the boundaries from the bytes of a boundaries file (`*.ser`)
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<CountryBoundaries, JsError> {
+        Ok(Self(country_boundaries::CountryBoundaries::from_reader(
+            bytes,
+        )?))
+    }
+
+    /// Loads the boundaries from the data with a raster of 60x30 cells that is compiled into
+    /// this module. The data is © OpenStreetMap contributors, licensed under the ODbL.
+    #[wasm_bindgen(js_name = fromDefaultData)]
+    pub fn from_default_data() -> Result<CountryBoundaries, JsError> {
+        Self::from_bytes(BOUNDARIES_ODBL_60X30)
+    }
+
+    /// Returns the ids of the regions the given position is in, ordered by size of the region
+    /// ascending
+    pub fn ids(&self, latitude: f64, longitude: f64) -> Result<Vec<String>, JsError> {
+        let position = LatLon::new(latitude, longitude)?;
+        Ok(to_strings(self.0.ids(position)))
+    }
+
+    /// Returns whether the given position is in the region with the given id
+    #[wasm_bindgen(js_name = isIn)]
+    pub fn is_in(&self, latitude: f64, longitude: f64, id: &str) -> Result<bool, JsError> {
+        Ok(self.0.is_in(LatLon::new(latitude, longitude)?, id))
+    }
+
+    /// Returns whether the given position is in any of the regions with the given ids
+    #[wasm_bindgen(js_name = isInAny)]
+    pub fn is_in_any(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        ids: Vec<String>,
+    ) -> Result<bool, JsError> {
+        let ids: HashSet<&str> = ids.iter().map(String::as_str).collect();
+        Ok(self.0.is_in_any(LatLon::new(latitude, longitude)?, &ids))
+    }
+
+    /// Returns the ids of the regions that fully contain the given bounding box
+    #[wasm_bindgen(js_name = containingIds)]
+    pub fn containing_ids(
+        &self,
+        min_latitude: f64,
+        min_longitude: f64,
+        max_latitude: f64,
+        max_longitude: f64,
+    ) -> Result<Vec<String>, JsError> {
+        let bounds = BoundingBox::new(min_latitude, min_longitude, max_latitude, max_longitude)?;
+        Ok(sorted(self.0.containing_ids(bounds)))
+    }
+
+    /// Returns the ids of the regions that contain or at least intersect with the given
+    /// bounding box
+    #[wasm_bindgen(js_name = intersectingIds)]
+    pub fn intersecting_ids(
+        &self,
+        min_latitude: f64,
+        min_longitude: f64,
+        max_latitude: f64,
+        max_longitude: f64,
+    ) -> Result<Vec<String>, JsError> {
+        let bounds = BoundingBox::new(min_latitude, min_longitude, max_latitude, max_longitude)?;
+        Ok(sorted(self.0.intersecting_ids(bounds)))
+    }
+
+    /// Returns the ids of all regions in the data
+    #[wasm_bindgen(js_name = allIds)]
+    pub fn all_ids(&self) -> Vec<String> {
+        sorted(self.0.all_ids())
+    }
+}
+
+fn to_strings(ids: Vec<&str>) -> Vec<String> {
+    ids.into_iter().map(String::from).collect()
+}
+
+fn sorted(ids: HashSet<&str>) -> Vec<String> {
+    let mut ids = to_strings(ids.into_iter().collect());
+    ids.sort();
+    ids
+}
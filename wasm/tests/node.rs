@@ -0,0 +1,68 @@
+//! Run with `wasm-pack test --node wasm`. The tests that do not fail also run natively with
+//! `cargo test`.
+
+use country_boundaries_wasm::CountryBoundaries;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn boundaries() -> CountryBoundaries {
+    CountryBoundaries::from_default_data().unwrap()
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn ids() {
+    assert_eq!(vec!["US-TX", "US"], boundaries().ids(33.0, -97.0).unwrap());
+    assert!(boundaries().ids(10.0, -30.0).unwrap().is_empty());
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn is_in() {
+    let boundaries = boundaries();
+    assert!(boundaries.is_in(33.0, -97.0, "US-TX").unwrap());
+    assert!(!boundaries.is_in(33.0, -97.0, "DE").unwrap());
+    assert!(boundaries
+        .is_in_any(33.0, -97.0, vec!["DE".into(), "US".into()])
+        .unwrap());
+    assert!(!boundaries.is_in_any(33.0, -97.0, vec![]).unwrap());
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn bounding_box_queries() {
+    let boundaries = boundaries();
+    // across the border between Texas and Oklahoma
+    assert_eq!(
+        vec!["US"],
+        boundaries.containing_ids(33.5, -98.0, 34.5, -97.0).unwrap()
+    );
+    let ids = boundaries
+        .intersecting_ids(33.5, -98.0, 34.5, -97.0)
+        .unwrap();
+    assert!(["US", "US-OK", "US-TX"]
+        .iter()
+        .all(|id| ids.contains(&id.to_string())));
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn all_ids() {
+    let ids = boundaries().all_ids();
+    assert!(ids.contains(&"DE".to_string()));
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+// errors are converted to JavaScript errors, so these only work in WebAssembly
+
+#[wasm_bindgen_test]
+fn invalid_position() {
+    assert!(boundaries().ids(91.0, 0.0).is_err());
+    assert!(boundaries().is_in(0.0, f64::NAN, "DE").is_err());
+}
+
+#[wasm_bindgen_test]
+fn invalid_bounding_box() {
+    assert!(boundaries().intersecting_ids(10.0, 0.0, 0.0, 1.0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn invalid_data() {
+    assert!(CountryBoundaries::from_bytes(&[1, 2, 3]).is_err());
+}